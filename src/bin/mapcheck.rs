@@ -0,0 +1,254 @@
+//! Checks REXPaint maps for mistakes the game would only trip over at runtime.
+//!
+//! Run `cargo run --bin mapcheck` to check every map in `assets/maps`, or pass the maps to
+//! check. Exits with a failure if any map has a problem.
+
+use ascii_survivors::{maps::*, resources::Level};
+use bevy::prelude::*;
+use rexpaint::XpFile;
+use std::{
+    collections::HashSet,
+    fs,
+    io::Cursor,
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+const MAP_DIRECTORY: &str = "assets/maps";
+
+fn main() -> ExitCode {
+    let tiles_path = Path::new("assets").join(TileRegistry::PATH);
+    let registry = match TileRegistry::load(&tiles_path) {
+        Ok(registry) => registry,
+        Err(error) => {
+            eprintln!("can't load tiles from {}: {error:#}", tiles_path.display());
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut paths: Vec<PathBuf> = std::env::args().skip(1).map(PathBuf::from).collect();
+    if paths.is_empty() {
+        match map_files(Path::new(MAP_DIRECTORY)) {
+            Ok(found) => paths = found,
+            Err(error) => {
+                eprintln!("can't read {MAP_DIRECTORY}: {error}");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    let mut failed = false;
+    for path in &paths {
+        let problems = check_map(path, &registry);
+        if problems.is_empty() {
+            println!("ok    {}", path.display());
+        } else {
+            failed = true;
+            println!("FAIL  {}", path.display());
+            for problem in problems {
+                println!("      {problem}");
+            }
+        }
+    }
+
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn map_files(directory: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut paths = Vec::new();
+    for entry in fs::read_dir(directory)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|extension| extension == "xp") {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+fn check_map(path: &Path, registry: &TileRegistry) -> Vec<String> {
+    let bytes = match fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(error) => return vec![format!("can't read the file: {error}")],
+    };
+
+    let name = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let map = match Map::from_xp_data(&bytes, name, registry.clone()) {
+        Ok(map) => map,
+        Err(error) => return vec![format!("{error:#}")],
+    };
+
+    // the level this map belongs to, if any
+    let level = Level::ALL.into_iter().find(|level| {
+        level
+            .map_path()
+            .is_some_and(|map_path| path.ends_with(map_path))
+    });
+
+    let mut problems = unknown_glyphs(&bytes, registry);
+    problems.extend(missing_markers(&map, level));
+    problems.extend(misplaced_markers(&map));
+    problems.extend(stray_puzzle_markers(&map));
+    problems.extend(unreachable_regions(&with_passages_open(&map)));
+    problems
+}
+
+/// glyphs the loader doesn't know, tiles silently become empty floor and the rest are dropped
+fn unknown_glyphs(bytes: &[u8], registry: &TileRegistry) -> Vec<String> {
+    let Ok(xp_file) = XpFile::read(&mut Cursor::new(bytes)) else {
+        return Vec::new();
+    };
+
+    let mut problems = Vec::new();
+    for (index, layer) in xp_file.layers.iter().enumerate().take(3) {
+        for (x, y, ch) in Map::layer_glyphs(layer) {
+            let (known, what) = match index {
+                0 => (registry.from_char(ch).is_some(), "tile"),
+                1 => (MarkerKind::from_char(ch).is_some(), "marker"),
+                _ => (ZoneKind::from_char(ch).is_some(), "zone"),
+            };
+            if !known {
+                problems.push(format!("unknown {what} glyph '{ch}' at ({x}, {y})"));
+            }
+        }
+    }
+    problems
+}
+
+fn missing_markers(map: &Map, level: Option<Level>) -> Vec<String> {
+    let mut problems = Vec::new();
+    if map.marker(MarkerKind::PlayerStart).is_none() {
+        problems.push("no player start marker '@'".to_string());
+    }
+
+    let needs_portal = level.is_none_or(|level| level.needs_portal_marker());
+    if needs_portal && map.marker(MarkerKind::Portal).is_none() {
+        problems.push("no portal marker 'P'".to_string());
+    }
+    problems
+}
+
+/// markers off the map, or on cells nothing could ever stand on
+fn misplaced_markers(map: &Map) -> Vec<String> {
+    let mut problems = Vec::new();
+    for marker in &map.markers {
+        let position = marker.position;
+        let Some(tile) = map
+            .in_bounds(position.x, position.y)
+            .then(|| map.tiles[position.x as usize][position.y as usize])
+        else {
+            problems.push(format!(
+                "{:?} marker at ({}, {}) is outside the {}x{} map",
+                marker.kind, position.x, position.y, map.width, map.height
+            ));
+            continue;
+        };
+
+        // launchers fire out of walls, locks sit on their doors and passages are walls until
+        // their puzzle is solved
+        let in_wall_allowed = matches!(
+            marker.kind,
+            MarkerKind::DartLauncher | MarkerKind::LockedDoor | MarkerKind::Passage
+        );
+        let def = map.tile_def(tile.tile_type);
+        let passable = def.walkable || tile.tile_type.is_door();
+        if !passable && !in_wall_allowed {
+            problems.push(format!(
+                "{:?} marker at ({}, {}) is on an impassable {} tile",
+                marker.kind, position.x, position.y, def.name
+            ));
+        }
+    }
+    problems
+}
+
+/// puzzle markers only do anything inside a puzzle zone
+fn stray_puzzle_markers(map: &Map) -> Vec<String> {
+    let puzzle_zones: Vec<&Zone> = map
+        .zones
+        .iter()
+        .filter(|zone| zone.kind == ZoneKind::Puzzle)
+        .collect();
+
+    let mut problems = Vec::new();
+    for marker in &map.markers {
+        let is_puzzle_marker = matches!(
+            marker.kind,
+            MarkerKind::Brazier | MarkerKind::Rune | MarkerKind::Passage | MarkerKind::PuzzleReward
+        );
+        let position = marker.position;
+        if is_puzzle_marker && !puzzle_zones.iter().any(|zone| zone.contains(position)) {
+            problems.push(format!(
+                "{:?} marker at ({}, {}) isn't inside a puzzle zone 'p'",
+                marker.kind, position.x, position.y
+            ));
+        }
+    }
+    problems
+}
+
+/// the map as it is once every puzzle is solved, so hidden rooms don't count as unreachable
+fn with_passages_open(map: &Map) -> Map {
+    let mut opened = map.clone();
+    for marker in &map.markers {
+        let position = marker.position;
+        if marker.kind == MarkerKind::Passage
+            && let Some(tile) = opened.get_tile_mut(position.x, position.y)
+        {
+            tile.tile_type = TileType::STONE;
+        }
+    }
+    opened
+}
+
+/// walkable areas the player can't get to from the start, even with every door opened
+fn unreachable_regions(map: &Map) -> Vec<String> {
+    let Some(start) = map.marker(MarkerKind::PlayerStart) else {
+        return Vec::new();
+    };
+    let distances = map.walk_distances(start);
+
+    let passable = |position: IVec2| {
+        map.in_bounds(position.x, position.y) && {
+            let tile_type = map.tiles[position.x as usize][position.y as usize].tile_type;
+            map.tile_def(tile_type).walkable || tile_type.is_door()
+        }
+    };
+
+    let mut seen = HashSet::new();
+    let mut problems = Vec::new();
+    for x in 0..map.width as i32 {
+        for y in 0..map.height as i32 {
+            let position = IVec2::new(x, y);
+            let reached = distances[map.cell_index(position)].is_some();
+            if reached || !passable(position) || !seen.insert(position) {
+                continue;
+            }
+
+            // flood the whole region so it's reported once
+            let mut size = 0;
+            let mut frontier = vec![position];
+            while let Some(cell) = frontier.pop() {
+                size += 1;
+                for offset in [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y] {
+                    let next = cell + offset;
+                    if passable(next) && seen.insert(next) {
+                        frontier.push(next);
+                    }
+                }
+            }
+
+            problems.push(format!(
+                "{size} walkable cells around ({x}, {y}) can't be reached from the player start"
+            ));
+        }
+    }
+    problems
+}
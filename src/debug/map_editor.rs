@@ -0,0 +1,420 @@
+use crate::{
+    maps::*,
+    objects::*,
+    resources::*,
+    systems::{LevelScoped, world_to_screen},
+};
+use bevy::prelude::*;
+use bevy_ascii_terminal::{Terminal, TerminalCamera, TerminalTransform, string::TerminalString};
+use bevy_kira_audio::prelude::*;
+use std::{fs::File, path::PathBuf};
+
+const EDITOR_KEY: KeyCode = KeyCode::F2;
+
+/// shown instead of saving, generated levels come from a seed rather than a map file
+const GENERATED_STATUS: &str = "generated level, saving is off";
+
+/// Paints tiles, markers and zones straight onto a level's map, play-tests it and saves it
+/// back to REXPaint. F2 opens it from the menu or mid-game, and returns to it from a play-test.
+pub struct MapEditorPlugin;
+
+impl Plugin for MapEditorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(GameState::Editor),
+            (clear_playtest, open_map_editor).chain(),
+        )
+        .add_systems(OnExit(GameState::Editor), show_lighting_overlay)
+        .add_systems(OnEnter(GameState::Menu), end_playtest)
+        .add_systems(
+            Update,
+            (
+                enter_map_editor.run_if(in_state(GameState::Menu).or(in_state(GameState::Game))),
+                (map_editor_input, render_map_editor)
+                    .chain()
+                    .run_if(in_state(GameState::Editor)),
+            ),
+        );
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditorLayer {
+    Tiles,
+    Markers,
+    Zones,
+}
+
+impl EditorLayer {
+    fn next(self) -> Self {
+        match self {
+            EditorLayer::Tiles => EditorLayer::Markers,
+            EditorLayer::Markers => EditorLayer::Zones,
+            EditorLayer::Zones => EditorLayer::Tiles,
+        }
+    }
+
+    fn brush_count(self, registry: &TileRegistry) -> usize {
+        match self {
+            EditorLayer::Tiles => registry.len(),
+            EditorLayer::Markers => MarkerKind::ALL.len(),
+            EditorLayer::Zones => ZoneKind::ALL.len(),
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct MapEditor {
+    pub map: Map,
+    /// where the map is saved, relative to the working directory. `None` for generated levels,
+    /// which are rebuilt from their seed and never read back from a file
+    pub path: Option<PathBuf>,
+    pub cursor: IVec2,
+    /// added to world positions to place them on the terminal, like `CameraOffset`
+    pub view: IVec2,
+    pub layer: EditorLayer,
+    /// index into the current layer's kinds
+    pub brush: usize,
+    pub status: String,
+}
+
+impl MapEditor {
+    fn new(map: Map, path: Option<PathBuf>) -> Self {
+        let cursor = IVec2::new(map.width as i32 / 2, map.height as i32 / 2);
+        let status = if path.is_some() {
+            String::new()
+        } else {
+            GENERATED_STATUS.to_string()
+        };
+        Self {
+            map,
+            path,
+            cursor,
+            view: IVec2::ZERO,
+            layer: EditorLayer::Tiles,
+            brush: 0,
+            status,
+        }
+    }
+
+    fn tile_brush(&self) -> TileType {
+        self.map
+            .registry
+            .all()
+            .nth(self.brush)
+            .unwrap_or(TileType::EMPTY)
+    }
+
+    fn brush_name(&self) -> String {
+        match self.layer {
+            EditorLayer::Tiles => {
+                let def = self.map.tile_def(self.tile_brush());
+                format!("{} '{}'", def.name, def.glyph)
+            }
+            EditorLayer::Markers => {
+                let kind = MarkerKind::ALL[self.brush];
+                format!("{:?} '{}'", kind, kind.to_char())
+            }
+            EditorLayer::Zones => {
+                let kind = ZoneKind::ALL[self.brush];
+                format!("{:?} '{}'", kind, kind.to_char())
+            }
+        }
+    }
+
+    /// paints the cursor's cell with the brush, or clears it on the current layer
+    fn paint(&mut self, erase: bool) {
+        let position = self.cursor;
+        if !self.map.in_bounds(position.x, position.y) {
+            return;
+        }
+
+        match self.layer {
+            EditorLayer::Tiles => {
+                let tile_type = if erase {
+                    TileType::EMPTY
+                } else {
+                    self.tile_brush()
+                };
+                self.map.tiles[position.x as usize][position.y as usize] = Tile::new(tile_type);
+            }
+            EditorLayer::Markers => {
+                let kind = (!erase).then(|| MarkerKind::ALL[self.brush]);
+                self.map.set_marker(position, kind);
+            }
+            EditorLayer::Zones => {
+                let kind = (!erase).then(|| ZoneKind::ALL[self.brush]);
+                self.map.set_zone(position, kind);
+            }
+        }
+    }
+
+    /// scrolls the view so the cursor stays clear of the status and help rows
+    fn follow_cursor(&mut self, terminal_size: UVec2) {
+        let screen = self.cursor + self.view;
+        let max = terminal_size.as_ivec2() - IVec2::new(1, 2);
+        self.view += (IVec2::new(0, 1) - screen).max(IVec2::ZERO);
+        self.view -= (screen - max).max(IVec2::ZERO);
+    }
+
+    fn save(&mut self) {
+        let Some(path) = &self.path else {
+            self.status = GENERATED_STATUS.to_string();
+            return;
+        };
+        let result = File::create(path).and_then(|mut file| self.map.to_xp().write(&mut file));
+        self.status = match result {
+            Ok(()) => {
+                info!("saved map to {}", path.display());
+                format!("saved {}", path.display())
+            }
+            Err(error) => {
+                error!("failed to save map to {}: {}", path.display(), error);
+                format!("save failed: {error}")
+            }
+        };
+    }
+}
+
+fn enter_map_editor(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if keyboard_input.just_pressed(EDITOR_KEY) {
+        next_state.set(GameState::Editor);
+    }
+}
+
+/// everything a play-test leaves behind
+type PlaytestLeftovers = Or<(
+    With<Player>,
+    With<Enemy>,
+    With<Boss>,
+    With<Projectile>,
+    With<Orb>,
+    With<LevelScoped>,
+)>;
+
+/// drops whatever was left of the last play-test, so the next one starts fresh
+fn clear_playtest(
+    mut commands: Commands,
+    entity_query: Query<Entity, PlaytestLeftovers>,
+    mut overlay_query: Query<&mut Visibility, With<LightingOverlaySprite>>,
+    music_channel: Res<AudioChannel<Music>>,
+) {
+    for entity in entity_query.iter() {
+        commands.entity(entity).despawn();
+    }
+    for mut visibility in overlay_query.iter_mut() {
+        *visibility = Visibility::Hidden;
+    }
+    music_channel.stop();
+}
+
+fn show_lighting_overlay(mut overlay_query: Query<&mut Visibility, With<LightingOverlaySprite>>) {
+    for mut visibility in overlay_query.iter_mut() {
+        *visibility = Visibility::Inherited;
+    }
+}
+
+/// a game started from the menu plays the real levels again
+fn end_playtest(mut commands: Commands) {
+    commands.remove_resource::<PlaytestMap>();
+}
+
+/// starts editing the current level's map, unless we're back from play-testing one
+fn open_map_editor(
+    mut commands: Commands,
+    editor: Option<Res<MapEditor>>,
+    level: Res<Level>,
+    map_handles: Res<MapHandles>,
+    (maps, registry): (Res<Assets<Map>>, Res<TileRegistry>),
+    current_map: Option<Res<Map>>,
+) {
+    if editor.is_some() {
+        return;
+    }
+
+    let asset = map_handles.get(*level).and_then(|handle| maps.get(handle));
+    let (mut map, path) = match (asset, level.map_path()) {
+        (Some(map), Some(path)) => (map.clone(), Some(PathBuf::from("assets").join(path))),
+        // generated levels start from whatever was generated last and can only be play-tested
+        _ => {
+            let map = current_map
+                .map(|map| map.clone())
+                .unwrap_or_else(|| Map::new(80, 50, "untitled".to_string(), registry.clone()));
+            (map, None)
+        }
+    };
+    map.terrain = None;
+
+    commands.insert_resource(MapEditor::new(map, path));
+}
+
+fn map_editor_input(
+    mut commands: Commands,
+    editor: Option<ResMut<MapEditor>>,
+    (keyboard_input, mouse_input): (Res<ButtonInput<KeyCode>>, Res<ButtonInput<MouseButton>>),
+    (terminal_query, camera_query): (
+        Query<(&Terminal, &TerminalTransform)>,
+        Query<&TerminalCamera>,
+    ),
+    mut next_state: ResMut<NextState<GameState>>,
+    mut last_mouse_cell: Local<Option<IVec2>>,
+) {
+    let Some(mut editor) = editor else {
+        return;
+    };
+    let Ok((terminal, terminal_transform)) = terminal_query.single() else {
+        return;
+    };
+    let terminal_size = terminal.size();
+
+    if keyboard_input.just_pressed(KeyCode::Escape) {
+        commands.remove_resource::<MapEditor>();
+        next_state.set(GameState::Menu);
+        return;
+    }
+
+    if keyboard_input.just_pressed(KeyCode::KeyP) {
+        commands.insert_resource(PlaytestMap(editor.map.clone()));
+        next_state.set(GameState::Game);
+        return;
+    }
+
+    let control = keyboard_input.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    if control && keyboard_input.just_pressed(KeyCode::KeyS) {
+        editor.save();
+    }
+
+    if keyboard_input.just_pressed(KeyCode::Tab) {
+        editor.layer = editor.layer.next();
+        editor.brush = 0;
+    }
+    let brush_count = editor.layer.brush_count(&editor.map.registry);
+    if keyboard_input.just_pressed(KeyCode::KeyE) {
+        editor.brush = (editor.brush + 1) % brush_count;
+    }
+    if keyboard_input.just_pressed(KeyCode::KeyQ) {
+        editor.brush = (editor.brush + brush_count - 1) % brush_count;
+    }
+
+    // up and down follow the world's y axis, same as player movement
+    let mut step = IVec2::ZERO;
+    if keyboard_input.just_pressed(KeyCode::ArrowUp) {
+        step.y += 1;
+    }
+    if keyboard_input.just_pressed(KeyCode::ArrowDown) {
+        step.y -= 1;
+    }
+    if keyboard_input.just_pressed(KeyCode::ArrowLeft) {
+        step.x -= 1;
+    }
+    if keyboard_input.just_pressed(KeyCode::ArrowRight) {
+        step.x += 1;
+    }
+    editor.cursor += step;
+
+    // the mouse only takes over the cursor when it moves to another cell
+    let mouse_cell = camera_query
+        .single()
+        .ok()
+        .and_then(|camera| camera.cursor_world_pos())
+        .and_then(|position| terminal_transform.world_to_tile(position));
+    if let Some(cell) = mouse_cell
+        && *last_mouse_cell != Some(cell)
+    {
+        // tile positions count up from the bottom of the terminal, like the world does
+        editor.cursor = cell - editor.view;
+    }
+    *last_mouse_cell = mouse_cell;
+
+    let max = IVec2::new(editor.map.width as i32 - 1, editor.map.height as i32 - 1);
+    editor.cursor = editor.cursor.clamp(IVec2::ZERO, max);
+
+    let painting = keyboard_input.pressed(KeyCode::Space) || mouse_input.pressed(MouseButton::Left);
+    let erasing =
+        keyboard_input.pressed(KeyCode::Backspace) || mouse_input.pressed(MouseButton::Right);
+    if painting || erasing {
+        editor.paint(erasing);
+    }
+
+    editor.follow_cursor(terminal_size);
+}
+
+fn render_map_editor(editor: Option<Res<MapEditor>>, mut terminal_query: Query<&mut Terminal>) {
+    let Some(editor) = editor else {
+        return;
+    };
+    let Ok(mut terminal) = terminal_query.single_mut() else {
+        return;
+    };
+    terminal.clear();
+
+    let terminal_size = terminal.size();
+    let map = &editor.map;
+
+    for x in 0..terminal_size.x as i32 {
+        for y in 0..terminal_size.y as i32 {
+            let draw_position = IVec2::new(x, y);
+            let world_position = world_to_screen(draw_position, terminal_size) - editor.view;
+
+            if !map.in_bounds(world_position.x, world_position.y) {
+                continue;
+            }
+
+            // every tile is shown, there's no fog of war while editing
+            let tile_type =
+                map.tiles[world_position.x as usize][world_position.y as usize].tile_type;
+            let (glyph, color) = match map
+                .markers
+                .iter()
+                .find(|marker| marker.position == world_position)
+            {
+                Some(marker) => (marker.kind.to_char(), Color::linear_rgb(1.0, 1.0, 0.0)),
+                None => {
+                    let def = map.tile_def(tile_type);
+                    (def.glyph, def.color())
+                }
+            };
+            let background = if world_position == editor.cursor {
+                Color::linear_rgb(0.4, 0.4, 0.8)
+            } else {
+                match map.zones_at(world_position).next() {
+                    Some(zone) => zone_color(zone.kind),
+                    None => Color::linear_rgb(0.02, 0.02, 0.02),
+                }
+            };
+
+            let mut cell = TerminalString::from(glyph.to_string());
+            cell.decoration.fg_color = Some(LinearRgba::from(color));
+            cell.decoration.bg_color = Some(LinearRgba::from(background));
+            terminal.put_string([x, y], cell);
+        }
+    }
+
+    let status = format!(
+        "{:?}: {}  ({}, {})  {}",
+        editor.layer,
+        editor.brush_name(),
+        editor.cursor.x,
+        editor.cursor.y,
+        editor.status
+    );
+    terminal.put_string([0, 0], status.as_str());
+    terminal.put_string(
+        [0, terminal_size.y as i32 - 1],
+        "Tab layer  Q/E brush  Space paint  Bksp erase  P test  Ctrl+S save  Esc quit",
+    );
+}
+
+fn zone_color(kind: ZoneKind) -> Color {
+    match kind {
+        ZoneKind::SafeZone => Color::linear_rgb(0.0, 0.15, 0.0),
+        ZoneKind::SpawnZone(SpawnCategory::Horde) => Color::linear_rgb(0.15, 0.0, 0.0),
+        ZoneKind::SpawnZone(SpawnCategory::Snake) => Color::linear_rgb(0.1, 0.1, 0.0),
+        ZoneKind::SpawnZone(SpawnCategory::Giant) => Color::linear_rgb(0.12, 0.0, 0.12),
+        ZoneKind::SpawnZone(SpawnCategory::Item) => Color::linear_rgb(0.0, 0.05, 0.15),
+        ZoneKind::Puzzle => Color::linear_rgb(0.15, 0.08, 0.0),
+    }
+}
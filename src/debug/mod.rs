@@ -1,12 +1,14 @@
 use bevy::prelude::{App, Plugin};
 
+mod map_editor;
 mod performance_overlay;
+pub use map_editor::*;
 pub use performance_overlay::*;
 
 pub struct DebugPlugins;
 
 impl Plugin for DebugPlugins {
     fn build(&self, app: &mut App) {
-        app.add_plugins(PerformanceOverlayPlugin);
+        app.add_plugins((PerformanceOverlayPlugin, MapEditorPlugin));
     }
 }
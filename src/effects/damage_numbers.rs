@@ -0,0 +1,143 @@
+use crate::{
+    resources::{CameraEffects, CameraOffset, LightingOverlay},
+    systems::{LevelScoped, world_to_screen},
+};
+use bevy::prelude::*;
+use bevy_ascii_terminal::{GridSize, Terminal, string::TerminalString};
+
+/// seconds a damage number stays up
+const DAMAGE_NUMBER_LIFETIME: f32 = 0.8;
+
+/// cells a damage number rises every second
+const DAMAGE_NUMBER_RISE: f32 = 4.0;
+
+/// seconds a struck entity's glyph stays flashed
+const HIT_FLASH_SECONDS: f32 = 0.12;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DamageKind {
+    Normal,
+    /// a hit on a boss' weak spot
+    Weakspot,
+    Critical,
+    Heal,
+}
+
+impl DamageKind {
+    pub fn color(self) -> Color {
+        match self {
+            DamageKind::Normal => Color::linear_rgb(1.0, 1.0, 0.8),
+            DamageKind::Weakspot => Color::linear_rgb(1.0, 0.55, 0.0),
+            DamageKind::Critical => Color::linear_rgb(1.0, 0.15, 0.15),
+            DamageKind::Heal => Color::linear_rgb(0.2, 1.0, 0.3),
+        }
+    }
+}
+
+/// a number floating up from where something was hurt or healed
+#[derive(Component)]
+pub struct DamageNumber {
+    /// world position, in cells
+    pub position: Vec2,
+    pub text: String,
+    pub kind: DamageKind,
+    pub age: f32,
+}
+
+impl DamageNumber {
+    pub fn spawn(commands: &mut Commands, cell: IVec2, amount: f32, kind: DamageKind) {
+        let amount = amount.round().max(1.0);
+        let text = match kind {
+            DamageKind::Heal => format!("+{amount}"),
+            DamageKind::Critical => format!("{amount}!"),
+            DamageKind::Normal | DamageKind::Weakspot => format!("{amount}"),
+        };
+        commands.spawn((
+            DamageNumber {
+                position: cell.as_vec2(),
+                text,
+                kind,
+                age: 0.0,
+            },
+            LevelScoped,
+        ));
+    }
+}
+
+/// briefly swaps a struck entity's glyph to black on white. children of a struck entity,
+/// like a boss' segments, flash along with it
+#[derive(Component)]
+pub struct HitFlash(pub Timer);
+
+impl Default for HitFlash {
+    fn default() -> Self {
+        Self(Timer::from_seconds(HIT_FLASH_SECONDS, TimerMode::Once))
+    }
+}
+
+pub fn update_damage_numbers(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut number_query: Query<(Entity, &mut DamageNumber)>,
+) {
+    let delta = time.delta_secs();
+    for (entity, mut number) in number_query.iter_mut() {
+        number.age += delta;
+        number.position.y += DAMAGE_NUMBER_RISE * delta;
+        if number.age >= DAMAGE_NUMBER_LIFETIME {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+pub fn update_hit_flashes(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut flash_query: Query<(Entity, &mut HitFlash)>,
+) {
+    for (entity, mut flash) in flash_query.iter_mut() {
+        flash.0.tick(time.delta());
+        if flash.0.finished() {
+            commands.entity(entity).remove::<HitFlash>();
+        }
+    }
+}
+
+/// draws damage numbers over everything, their cells are kept out of the lighting so they
+/// read clearly in the dark
+pub fn render_damage_numbers(
+    number_query: Query<&DamageNumber>,
+    mut terminal_query: Query<&mut Terminal>,
+    mut overlay: Option<ResMut<LightingOverlay>>,
+    camera_offset: Res<CameraOffset>,
+    camera_effects: Res<CameraEffects>,
+) {
+    let Ok(mut terminal) = terminal_query.single_mut() else {
+        return;
+    };
+    let terminal_size = terminal.size();
+    let camera_offset = camera_effects.view_offset(&camera_offset);
+
+    for number in number_query.iter() {
+        let cell = number.position.round().as_ivec2();
+        let center = world_to_screen(cell + camera_offset, terminal_size);
+        let start = center - IVec2::new(number.text.chars().count() as i32 / 2, 0);
+
+        // numbers fade out over the last part of their life
+        let fade = ((DAMAGE_NUMBER_LIFETIME - number.age) / 0.3).clamp(0.0, 1.0);
+        let color = (LinearRgba::from(number.kind.color()) * fade).with_alpha(1.0);
+
+        for (index, glyph) in number.text.chars().enumerate() {
+            let screen = start + IVec2::new(index as i32, 0);
+            if !terminal_size.contains_point([screen.x, screen.y]) {
+                continue;
+            }
+            let mut glyph = TerminalString::from(glyph.to_string());
+            glyph.decoration.fg_color = Some(color);
+            terminal.put_string([screen.x, screen.y], glyph);
+            if let Some(overlay) = overlay.as_mut() {
+                overlay.leave_unlit(screen.as_uvec2());
+            }
+        }
+    }
+}
@@ -1,5 +1,9 @@
+pub mod damage_numbers;
 pub mod fireball;
+pub mod particles;
 pub mod status_effect;
 
+pub use damage_numbers::*;
 pub use fireball::*;
+pub use particles::*;
 pub use status_effect::*;
@@ -0,0 +1,289 @@
+use crate::{objects::*, systems::LevelScoped};
+use bevy::prelude::*;
+use rand::prelude::*;
+use std::{f32::consts::PI, ops::Range, sync::Arc};
+
+/// What a particle looks like as it ages. Both lists are spread evenly over its lifetime,
+/// colors blend into one another while glyphs just switch over.
+#[derive(Debug, Clone)]
+pub struct ParticleStyle {
+    pub glyphs: Vec<char>,
+    pub colors: Vec<Color>,
+    pub layer: RenderLayer,
+}
+
+impl ParticleStyle {
+    /// `age` runs from 0 when a particle is spawned to 1 when it dies
+    pub fn glyph_at(&self, age: f32) -> char {
+        let last = self.glyphs.len().saturating_sub(1);
+        let index = (age.clamp(0.0, 1.0) * self.glyphs.len() as f32) as usize;
+        self.glyphs.get(index.min(last)).copied().unwrap_or('.')
+    }
+
+    pub fn color_at(&self, age: f32) -> Color {
+        let Some(&first) = self.colors.first() else {
+            return Color::WHITE;
+        };
+        let steps = (self.colors.len() - 1) as f32;
+        let position = age.clamp(0.0, 1.0) * steps;
+        let index = position as usize;
+        match self.colors.get(index + 1) {
+            Some(&next) => {
+                let from = LinearRgba::from(self.colors[index]);
+                Color::from(from.mix(&LinearRgba::from(next), position.fract()))
+            }
+            None => self.colors.last().copied().unwrap_or(first),
+        }
+    }
+}
+
+/// Spawns particles from a spot in the world, `rate` of them a second until `duration`
+/// runs out, or forever without one. Emitters that run out take their entity with them,
+/// so they belong on an entity of their own. One-off effects can call `burst` instead.
+#[derive(Component, Clone)]
+pub struct ParticleEmitter {
+    /// world position particles start from, in cells
+    pub origin: Vec2,
+    /// particles start anywhere up to this far from the origin
+    pub area: Vec2,
+    pub rate: f32,
+    pub duration: Option<Timer>,
+    /// seconds each particle lives
+    pub lifetime: Range<f32>,
+    /// the average starting velocity, in cells per second
+    pub velocity: Vec2,
+    /// how far off the average direction particles may fly, in radians either way
+    pub spread: f32,
+    /// starting speeds vary by up to this fraction of the average
+    pub speed_jitter: f32,
+    /// pulls on particles every second, in cells per second
+    pub gravity: Vec2,
+    pub style: Arc<ParticleStyle>,
+    /// particles owed since the last one was spawned
+    pending: f32,
+}
+
+impl ParticleEmitter {
+    pub fn new(origin: Vec2, style: ParticleStyle) -> Self {
+        Self {
+            origin,
+            area: Vec2::ZERO,
+            rate: 0.0,
+            duration: None,
+            lifetime: 0.5..1.0,
+            velocity: Vec2::ZERO,
+            spread: PI,
+            speed_jitter: 0.0,
+            gravity: Vec2::ZERO,
+            style: Arc::new(style),
+            pending: 0.0,
+        }
+    }
+
+    /// sparks drifting up from a fire
+    pub fn embers(cell: IVec2) -> Self {
+        Self {
+            rate: 1.5,
+            lifetime: 0.8..1.6,
+            velocity: Vec2::new(0.0, 3.0),
+            spread: 0.5,
+            speed_jitter: 0.4,
+            gravity: Vec2::new(0.0, -1.0),
+            ..Self::new(
+                cell.as_vec2() + Vec2::Y,
+                ParticleStyle {
+                    glyphs: vec!['*', '\'', '.'],
+                    colors: vec![
+                        Color::linear_rgb(1.0, 0.8, 0.2),
+                        Color::linear_rgb(1.0, 0.5, 0.0),
+                        Color::linear_rgb(0.4, 0.1, 0.0),
+                    ],
+                    layer: RenderLayer::Projectiles,
+                },
+            )
+        }
+    }
+
+    /// a ball of fire thrown out in every direction, meant to be burst
+    pub fn explosion(cell: IVec2) -> Self {
+        Self {
+            lifetime: 0.25..0.6,
+            velocity: Vec2::new(9.0, 0.0),
+            speed_jitter: 0.6,
+            ..Self::new(
+                cell.as_vec2(),
+                ParticleStyle {
+                    glyphs: vec!['#', '*', '+', '.'],
+                    colors: vec![
+                        Color::linear_rgb(1.0, 1.0, 0.6),
+                        Color::linear_rgb(1.0, 0.5, 0.0),
+                        Color::linear_rgb(0.5, 0.1, 0.0),
+                    ],
+                    layer: RenderLayer::Projectiles,
+                },
+            )
+        }
+    }
+
+    /// bits of whatever was just smashed, falling back to the ground, meant to be burst
+    pub fn shards(cell: IVec2, color: Color) -> Self {
+        Self {
+            lifetime: 0.3..0.7,
+            velocity: Vec2::new(0.0, 6.0),
+            spread: 1.2,
+            speed_jitter: 0.5,
+            gravity: Vec2::new(0.0, -18.0),
+            ..Self::new(
+                cell.as_vec2(),
+                ParticleStyle {
+                    glyphs: vec!['\'', ',', '.'],
+                    colors: vec![color, color.darker(0.3)],
+                    layer: RenderLayer::Items,
+                },
+            )
+        }
+    }
+
+    /// glitter hanging around a spot for a while
+    pub fn sparkles(cell: IVec2, color: Color, seconds: f32) -> Self {
+        Self {
+            area: Vec2::splat(1.5),
+            rate: 8.0,
+            duration: Some(Timer::from_seconds(seconds, TimerMode::Once)),
+            lifetime: 0.2..0.5,
+            velocity: Vec2::new(0.0, 0.5),
+            ..Self::new(
+                cell.as_vec2(),
+                ParticleStyle {
+                    glyphs: vec!['+', '*', '.'],
+                    colors: vec![Color::WHITE, color],
+                    layer: RenderLayer::Projectiles,
+                },
+            )
+        }
+    }
+
+    /// a puff kicked up from the ground, meant to be burst
+    pub fn dust(cell: IVec2) -> Self {
+        Self {
+            lifetime: 0.3..0.6,
+            velocity: Vec2::new(2.0, 0.0),
+            speed_jitter: 0.5,
+            ..Self::new(
+                cell.as_vec2(),
+                ParticleStyle {
+                    glyphs: vec!['░', '.'],
+                    colors: vec![
+                        Color::linear_rgb(0.45, 0.4, 0.3),
+                        Color::linear_rgb(0.2, 0.18, 0.15),
+                    ],
+                    layer: RenderLayer::Items,
+                },
+            )
+        }
+    }
+
+    pub fn finished(&self) -> bool {
+        self.duration.as_ref().is_some_and(Timer::finished)
+    }
+
+    /// spawns a number of particles at once
+    pub fn burst(&self, commands: &mut Commands, count: u32) {
+        let mut rng = rand::rng();
+        for _ in 0..count {
+            commands.spawn((self.particle(&mut rng), LevelScoped));
+        }
+    }
+
+    fn particle(&self, rng: &mut impl Rng) -> Particle {
+        let offset = Vec2::new(
+            rng.random_range(-1.0..=1.0) * self.area.x,
+            rng.random_range(-1.0..=1.0) * self.area.y,
+        );
+        let angle = rng.random_range(-1.0..=1.0) * self.spread;
+        let speed = 1.0 + rng.random_range(-1.0..=1.0) * self.speed_jitter;
+        let lifetime = if self.lifetime.is_empty() {
+            self.lifetime.start
+        } else {
+            rng.random_range(self.lifetime.clone())
+        };
+
+        Particle {
+            position: self.origin + offset,
+            velocity: Vec2::from_angle(angle).rotate(self.velocity) * speed,
+            gravity: self.gravity,
+            age: 0.0,
+            lifetime: lifetime.max(f32::EPSILON),
+            style: self.style.clone(),
+        }
+    }
+}
+
+/// one glyph's worth of an effect, moving at sub-cell precision and drawn on its nearest cell
+#[derive(Component)]
+#[require(Renderable)]
+pub struct Particle {
+    pub position: Vec2,
+    pub velocity: Vec2,
+    pub gravity: Vec2,
+    /// seconds since it was spawned
+    pub age: f32,
+    pub lifetime: f32,
+    pub style: Arc<ParticleStyle>,
+}
+
+impl Visual for Particle {
+    fn cell(&self) -> IVec2 {
+        self.position.round().as_ivec2()
+    }
+
+    fn restyle(&self, renderable: &mut Renderable) {
+        let age = self.age / self.lifetime;
+        renderable.glyph = self.style.glyph_at(age);
+        renderable.fg = self.style.color_at(age);
+        renderable.layer = self.style.layer;
+    }
+}
+
+pub fn emit_particles(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut emitter_query: Query<(Entity, &mut ParticleEmitter)>,
+) {
+    let mut rng = rand::rng();
+    for (entity, mut emitter) in emitter_query.iter_mut() {
+        if let Some(duration) = emitter.duration.as_mut() {
+            duration.tick(time.delta());
+        }
+
+        emitter.pending += emitter.rate * time.delta_secs();
+        while emitter.pending >= 1.0 {
+            emitter.pending -= 1.0;
+            commands.spawn((emitter.particle(&mut rng), LevelScoped));
+        }
+
+        if emitter.finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+pub fn update_particles(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut particle_query: Query<(Entity, &mut Particle)>,
+) {
+    let delta = time.delta_secs();
+    for (entity, mut particle) in particle_query.iter_mut() {
+        particle.age += delta;
+        if particle.age >= particle.lifetime {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        let gravity = particle.gravity;
+        particle.velocity += gravity * delta;
+        let velocity = particle.velocity;
+        particle.position += velocity * delta;
+    }
+}
@@ -1,8 +1,10 @@
 use crate::resources::timers::DamageEffectTimer;
 use bevy::prelude::*;
 
+/// a short lived effect on the player, tinting their glyph and listed on the hud
 #[derive(Component)]
 pub struct StatusEffect {
+    pub name: &'static str,
     pub color: Color,
 }
 
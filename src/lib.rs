@@ -0,0 +1,4 @@
+//! Everything the map tools share with the game.
+
+pub mod maps;
+pub mod resources;
@@ -1,14 +1,21 @@
 mod debug;
 mod effects;
-mod maps;
 mod objects;
-mod resources;
 mod scenes;
 mod spells;
 mod systems;
 
+use ascii_survivors::{maps, resources};
+
 use crate::{
-    debug::DebugPlugins, effects::*, objects::*, resources::*, scenes::*, spells::*, systems::*,
+    debug::DebugPlugins,
+    effects::*,
+    maps::{FlowField, MapHandles, MapLoaderPlugin, TileRegistry, report_map_load_failures},
+    objects::*,
+    resources::*,
+    scenes::*,
+    spells::*,
+    systems::*,
 };
 
 use bevy::{prelude::*, window::*};
@@ -30,6 +37,7 @@ fn main() {
             TerminalPlugins,
             AudioPlugin,
             GameScenesPlugin,
+            MapLoaderPlugin,
             #[cfg(debug_assertions)]
             DebugPlugins,
         ))
@@ -37,6 +45,8 @@ fn main() {
         .add_audio_channel::<Music>()
         .add_audio_channel::<Sfx>()
         .insert_resource(SpellInputTimer::default())
+        .insert_resource(ViewportConfig::from_env())
+        .insert_resource(CameraEffects::from_env())
         .add_systems(
             Startup,
             (
@@ -47,6 +57,11 @@ fn main() {
             )
                 .chain(),
         )
+        .add_systems(PreUpdate, (resize_terminal, fit_lighting_overlay).chain())
+        .add_systems(
+            PreUpdate,
+            (toggle_camera_effects, update_camera_effects).chain(),
+        )
         .add_systems(OnEnter(GameState::Loading), show_window)
         .add_systems(
             OnEnter(GameState::FadingIn),
@@ -54,7 +69,15 @@ fn main() {
         )
         .add_systems(
             OnEnter(GameState::Game),
-            (setup_game, play_theme, maps::map::load_map_system).chain(),
+            (
+                setup_game,
+                play_theme,
+                despawn_level_entities,
+                reset_floors,
+                maps::map::load_map_system,
+                spawn_map_markers,
+            )
+                .chain(),
         )
         .add_systems(
             OnEnter(GameState::LevelTransition),
@@ -69,15 +92,18 @@ fn main() {
         .add_systems(
             Update,
             (
-                loading_update_system.run_if(in_state(GameState::Loading)),
+                (report_map_load_failures, loading_update_system)
+                    .run_if(in_state(GameState::Loading)),
                 menu_input_system.run_if(in_state(GameState::Menu)),
                 fade_in_update_system.run_if(in_state(GameState::FadingIn)),
                 (
-                    player_movement,
+                    (player_movement, use_stairs, arrive_on_floor).chain(),
+                    stream_terrain,
+                    update_field_of_view,
+                    update_flow_field,
                     spawn_enemies,
                     spawn_bosses,
                     spawn_portal_after_survival,
-                    spawn_shop_npcs_on_rest_level,
                     interaction_system,
                     heal_player_system,
                     portal_transition_system,
@@ -85,22 +111,37 @@ fn main() {
                     (
                         enemy_ai,
                         boss_ai,
+                        trigger_traps,
+                        spot_hidden_traps,
+                        move_darts,
                         auto_cast,
                         process_projectiles,
                         process_fireballs,
                         process_collisions,
                         orb_movement,
                         process_orb_collection,
+                        process_key_pickup,
                         campfire_animation_system,
-                        ember_animation_system,
+                        (emit_particles, update_particles),
+                        (update_damage_numbers, update_hit_flashes),
                         light_flicker_system,
+                        update_light_puzzle_pieces,
+                        solve_light_puzzles,
                     )
                         .chain(),
                     update_status_effect,
                     death_detection_system,
                     spell_casting_system,
-                    systems::render::render_system,
-                    spell_render_system,
+                    (
+                        toggle_minimap,
+                        toggle_legend,
+                        systems::render::render_system,
+                        render_minimap,
+                        render_damage_numbers,
+                        render_hud,
+                        render_legend,
+                    )
+                        .chain(),
                     render_message_system,
                     render_portal_transition,
                     despawn_entities,
@@ -112,10 +153,34 @@ fn main() {
                     .run_if(in_state(GameState::GameOver)),
             ),
         )
+        .add_systems(
+            Update,
+            (
+                sync_renderables::<Player>,
+                sync_renderables::<Enemy>,
+                sync_renderables::<Projectile>,
+                sync_renderables::<Dart>,
+                sync_renderables::<Orb>,
+                sync_renderables::<DoorKey>,
+                sync_renderables::<Door>,
+                sync_renderables::<Trap>,
+                sync_renderables::<Breakable>,
+                sync_renderables::<Brazier>,
+                sync_renderables::<Rune>,
+                sync_renderables::<Mirror>,
+                sync_renderables::<Campfire>,
+                sync_renderables::<Particle>,
+                sync_renderables::<Portal>,
+                sync_renderables::<ShopNpc>,
+                sync_boss_segments,
+            )
+                .after(spell_casting_system)
+                .before(render_system),
+        )
         .add_systems(
             Update,
             update_lighting_overlay
-                .after(render_system)
+                .after(render_legend)
                 .run_if(in_state(GameState::Game)),
         )
         .run();
@@ -159,11 +224,23 @@ fn setup_resources(mut commands: Commands) {
     commands.insert_resource(SceneLock::default());
     commands.insert_resource(Ruleset::default());
     commands.insert_resource(Level::default());
+    commands.insert_resource(MapSeed::default());
     commands.insert_resource(KillCount::default());
+    commands.insert_resource(FlowField::default());
+    commands.insert_resource(Floors::default());
+    commands.insert_resource(Minimap::default());
+    commands.insert_resource(Legend::default());
 }
 
-fn setup(mut commands: Commands) {
-    commands.spawn(Terminal::new([80, 50]));
+fn setup(
+    mut commands: Commands,
+    viewport: Res<ViewportConfig>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+) {
+    let window_pixels = window_query
+        .single()
+        .map_or(UVec2::ZERO, |window| window.physical_size());
+    commands.spawn(Terminal::new(viewport.terminal_size(window_pixels)));
     commands.spawn(TerminalCamera::new());
 }
 
@@ -196,10 +273,17 @@ fn loading_update_system(
     time: Res<Time>,
     mut loading_timer: ResMut<LoadingTimer>,
     mut next_state: ResMut<NextState<GameState>>,
+    asset_server: Res<AssetServer>,
+    map_handles: Res<MapHandles>,
+    tile_registry: Option<Res<TileRegistry>>,
 ) {
     loading_timer.0.tick(time.delta());
 
-    if loading_timer.0.finished() {
+    // hold the loading screen until the tiles and every level's map are ready
+    if loading_timer.0.finished()
+        && tile_registry.is_some()
+        && map_handles.all_loaded(&asset_server)
+    {
         next_state.set(GameState::Menu);
     }
 }
@@ -287,7 +371,6 @@ fn setup_level_transition(
     enemy_query: Query<Entity, With<Enemy>>,
     projectile_query: Query<Entity, With<Projectile>>,
     orb_query: Query<Entity, With<Orb>>,
-    mut player_query: Query<&mut Player>,
     mut camera_offset: ResMut<CameraOffset>,
     level: Res<Level>,
     mut scene_lock: ResMut<SceneLock>,
@@ -302,27 +385,9 @@ fn setup_level_transition(
         commands.entity(entity).despawn();
     }
 
-    if let Ok(mut player) = player_query.single_mut() {
-        player.position = IVec2::new(40, 25);
-        player.world_position = IVec2::new(40, 25);
-    }
-
+    // the player and the level's objects are placed from the new map's markers
     camera_offset.0 = IVec2::default();
-
-    if level.as_ref() == &Level::Rest {
-        scene_lock.0 = true;
-        let campfire_position = IVec2::new(40, 25);
-
-        commands.spawn((
-            Campfire::new(campfire_position),
-            crate::objects::Interaction::new(InteractionType::Campfire), // todo: maybe we should reconsider naming it 'Interaction'
-            LightEmitter::campfire(),
-            LightFlicker::campfire(),
-            Transform::from_xyz(campfire_position.x as f32, campfire_position.y as f32, 0.0),
-        ));
-    } else {
-        scene_lock.0 = false;
-    }
+    scene_lock.0 = level.as_ref() == &Level::Rest;
 }
 
 fn level_transition_system(
@@ -0,0 +1,258 @@
+use crate::maps::{
+    dungeon::carve_corridor,
+    map::Map,
+    marker::{MapMarker, MarkerKind},
+    tile::{Tile, TileRegistry, TileType},
+};
+use bevy::prelude::*;
+use rand::{prelude::*, rngs::StdRng};
+
+/// chance a cell starts out as rock before smoothing
+const INITIAL_WALL_CHANCE: f64 = 0.45;
+
+const SMOOTHING_PASSES: usize = 5;
+
+/// rock neighbours (out of 8) that turn a cell to rock, one fewer keeps it as it is
+/// and anything less opens it up
+const WALL_NEIGHBOURS: usize = 5;
+
+/// open pockets smaller than this are filled in instead of tunnelled to
+const MIN_POCKET_SIZE: usize = 16;
+
+/// enemy spawn markers are spread over the far half of the cave
+const ENEMY_SPAWN_MARKERS: usize = 4;
+
+/// breakables scattered anywhere in the cave
+const LOOT_MARKERS: usize = 6;
+
+/// Generates a cave by cellular-automata smoothing of random noise.
+/// Every open cell ends up reachable from the player start, so the portal always is too.
+/// The same seed always produces the same layout.
+pub fn generate_cave(width: usize, height: usize, seed: u64, registry: TileRegistry) -> Map {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut map = Map::new(width, height, format!("Cave #{seed}"), registry);
+
+    let mut rock = vec![vec![true; height]; width];
+    for column in rock.iter_mut().take(width.saturating_sub(1)).skip(1) {
+        for cell in column.iter_mut().take(height.saturating_sub(1)).skip(1) {
+            *cell = rng.random_bool(INITIAL_WALL_CHANCE);
+        }
+    }
+
+    for _ in 0..SMOOTHING_PASSES {
+        rock = smooth(&rock);
+    }
+
+    for (x, column) in map.tiles.iter_mut().enumerate() {
+        for (y, tile) in column.iter_mut().enumerate() {
+            *tile = if rock[x][y] {
+                Tile::wall()
+            } else {
+                Tile::new(TileType::STONE)
+            };
+        }
+    }
+
+    connect_pockets(&mut map, &mut rng);
+
+    let open_cells = cells_of(&map, |tile| map.tile_def(tile.tile_type).walkable);
+    let center = IVec2::new(width as i32 / 2, height as i32 / 2);
+    let Some(start) = open_cells
+        .iter()
+        .copied()
+        .min_by_key(|cell| cell.distance_squared(center))
+    else {
+        return map;
+    };
+
+    // the portal goes on the open cell with the longest walk from the start
+    let distances = map.walk_distances(start);
+    let reachable: Vec<(IVec2, u32)> = open_cells
+        .iter()
+        .filter_map(|&cell| distances[map.cell_index(cell)].map(|distance| (cell, distance)))
+        .collect();
+    let furthest = reachable
+        .iter()
+        .copied()
+        .max_by_key(|&(_, distance)| distance);
+
+    map.markers
+        .push(MapMarker::new(MarkerKind::PlayerStart, start));
+
+    if let Some((portal, furthest_distance)) = furthest {
+        map.markers.push(MapMarker::new(MarkerKind::Portal, portal));
+
+        let far_cells: Vec<IVec2> = reachable
+            .iter()
+            .filter(|&&(cell, distance)| cell != portal && distance * 2 >= furthest_distance)
+            .map(|&(cell, _)| cell)
+            .collect();
+        for &cell in far_cells.sample(&mut rng, ENEMY_SPAWN_MARKERS) {
+            map.markers
+                .push(MapMarker::new(MarkerKind::EnemySpawn, cell));
+        }
+
+        let loot_cells: Vec<IVec2> = reachable
+            .iter()
+            .map(|&(cell, _)| cell)
+            .filter(|&cell| cell != start && cell != portal)
+            .collect();
+        for &cell in loot_cells.sample(&mut rng, LOOT_MARKERS) {
+            map.markers.push(MapMarker::new(MarkerKind::Loot, cell));
+        }
+    }
+
+    map
+}
+
+/// one automaton step, the edge of the map stays rock
+fn smooth(rock: &[Vec<bool>]) -> Vec<Vec<bool>> {
+    let width = rock.len() as i32;
+    let height = rock.first().map_or(0, Vec::len) as i32;
+    let mut next = rock.to_vec();
+
+    for x in 1..width - 1 {
+        for y in 1..height - 1 {
+            let mut neighbours = 0;
+            for dx in -1..=1 {
+                for dy in -1..=1 {
+                    if (dx != 0 || dy != 0) && rock[(x + dx) as usize][(y + dy) as usize] {
+                        neighbours += 1;
+                    }
+                }
+            }
+
+            if neighbours >= WALL_NEIGHBOURS {
+                next[x as usize][y as usize] = true;
+            } else if neighbours < WALL_NEIGHBOURS - 1 {
+                next[x as usize][y as usize] = false;
+            }
+        }
+    }
+
+    next
+}
+
+/// fills in tiny pockets and tunnels every other pocket into the largest one
+fn connect_pockets(map: &mut Map, rng: &mut StdRng) {
+    let mut pockets = open_regions(map);
+    pockets.sort_by_key(|pocket| std::cmp::Reverse(pocket.len()));
+
+    // a solid map still needs somewhere to stand
+    if pockets.is_empty() {
+        let center = IVec2::new(map.width as i32 / 2, map.height as i32 / 2);
+        if let Some(tile) = map.get_tile_mut(center.x, center.y) {
+            *tile = Tile::new(TileType::STONE);
+        }
+        return;
+    }
+
+    let mut connected = pockets.remove(0);
+    for pocket in pockets {
+        if pocket.len() < MIN_POCKET_SIZE {
+            for cell in pocket {
+                map.tiles[cell.x as usize][cell.y as usize] = Tile::wall();
+            }
+            continue;
+        }
+
+        let (from, to) = closest_cells(&pocket, &connected);
+        carve_corridor(map, rng, from, to);
+        connected.extend(pocket);
+    }
+}
+
+/// every 4-connected group of open cells
+fn open_regions(map: &Map) -> Vec<Vec<IVec2>> {
+    let mut seen = vec![false; map.width * map.height];
+    let mut regions = Vec::new();
+
+    for start in cells_of(map, |tile| map.tile_def(tile.tile_type).walkable) {
+        if seen[map.cell_index(start)] {
+            continue;
+        }
+
+        let mut region = Vec::new();
+        let mut stack = vec![start];
+        seen[map.cell_index(start)] = true;
+        while let Some(position) = stack.pop() {
+            region.push(position);
+            for offset in [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y] {
+                let next = position + offset;
+                let open = map
+                    .get_tile(next.x, next.y)
+                    .is_some_and(|tile| map.tile_def(tile.tile_type).walkable);
+                if open && !seen[map.cell_index(next)] {
+                    seen[map.cell_index(next)] = true;
+                    stack.push(next);
+                }
+            }
+        }
+
+        regions.push(region);
+    }
+
+    regions
+}
+
+fn closest_cells(from: &[IVec2], to: &[IVec2]) -> (IVec2, IVec2) {
+    let mut best = (from[0], to[0]);
+    let mut best_distance = i32::MAX;
+    for &a in from {
+        for &b in to {
+            let distance = (a - b).abs().element_sum();
+            if distance < best_distance {
+                best = (a, b);
+                best_distance = distance;
+            }
+        }
+    }
+    best
+}
+
+fn cells_of(map: &Map, predicate: impl Fn(&Tile) -> bool) -> Vec<IVec2> {
+    let mut cells = Vec::new();
+    for x in 0..map.width {
+        for y in 0..map.height {
+            if predicate(&map.tiles[x][y]) {
+                cells.push(IVec2::new(x as i32, y as i32));
+            }
+        }
+    }
+    cells
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maps::{
+        map::{GENERATED_HEIGHT, GENERATED_WIDTH},
+        tile::test_registry,
+    };
+
+    #[test]
+    fn portal_reachable_and_no_pocket_left_behind() {
+        let registry = test_registry();
+        for seed in 0..200 {
+            let map = generate_cave(GENERATED_WIDTH, GENERATED_HEIGHT, seed, registry.clone());
+            let start = map
+                .marker(MarkerKind::PlayerStart)
+                .unwrap_or_else(|| panic!("seed {seed} has no player start"));
+            let portal = map
+                .marker(MarkerKind::Portal)
+                .unwrap_or_else(|| panic!("seed {seed} has no portal"));
+            let distances = map.walk_distances(start);
+
+            assert!(
+                distances[map.cell_index(portal)].is_some(),
+                "seed {seed}: portal at {portal} is unreachable"
+            );
+            for cell in cells_of(&map, |tile| map.tile_def(tile.tile_type).walkable) {
+                assert!(
+                    distances[map.cell_index(cell)].is_some(),
+                    "seed {seed}: {cell} is in a pocket cut off from the start"
+                );
+            }
+        }
+    }
+}
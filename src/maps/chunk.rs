@@ -0,0 +1,161 @@
+use crate::maps::tile::{Tile, TileType};
+use bevy::prelude::*;
+use rand::{prelude::*, rngs::StdRng};
+use std::collections::HashMap;
+
+/// width and height of a chunk in cells
+pub const CHUNK_SIZE: i32 = 32;
+
+/// chunks within at least this many chunks of the player are kept loaded
+const MIN_LOAD_RADIUS: i32 = 2;
+
+/// chunks are only dropped once they're this many chunks past the load radius, so walking
+/// back and forth over a chunk border doesn't keep regenerating the same chunks
+const UNLOAD_MARGIN: i32 = 1;
+
+/// cells loaded past the edge of the screen, enough for the camera shake
+const VIEW_SLACK: i32 = 2;
+
+const GRASS_CHANCE: f64 = 0.12;
+const POND_CHANCE: f64 = 0.3;
+const OUTCROP_CHANCE: f64 = 0.3;
+const BOULDER_CHANCE: f64 = 0.4;
+
+#[derive(Clone)]
+struct Chunk {
+    tiles: Vec<Tile>,
+}
+
+impl Chunk {
+    fn index(local: IVec2) -> usize {
+        (local.x * CHUNK_SIZE + local.y) as usize
+    }
+}
+
+/// Endless terrain around a map, generated a chunk at a time from a seed.
+/// A chunk always comes out the same, so unloading one loses nothing but what the player explored.
+#[derive(Clone)]
+pub struct ChunkedTerrain {
+    seed: u64,
+    chunks: HashMap<IVec2, Chunk>,
+}
+
+impl ChunkedTerrain {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            chunks: HashMap::new(),
+        }
+    }
+
+    /// coordinates of the chunk holding a world position
+    pub fn chunk_of(position: IVec2) -> IVec2 {
+        position.div_euclid(IVec2::splat(CHUNK_SIZE))
+    }
+
+    pub fn is_loaded(&self, chunk: IVec2) -> bool {
+        self.chunks.contains_key(&chunk)
+    }
+
+    /// `None` if the chunk holding the position isn't loaded
+    pub fn get_tile(&self, position: IVec2) -> Option<&Tile> {
+        let chunk = self.chunks.get(&Self::chunk_of(position))?;
+        Some(&chunk.tiles[Chunk::index(position.rem_euclid(IVec2::splat(CHUNK_SIZE)))])
+    }
+
+    pub fn get_tile_mut(&mut self, position: IVec2) -> Option<&mut Tile> {
+        let chunk = self.chunks.get_mut(&Self::chunk_of(position))?;
+        Some(&mut chunk.tiles[Chunk::index(position.rem_euclid(IVec2::splat(CHUNK_SIZE)))])
+    }
+
+    pub fn tiles_mut(&mut self) -> impl Iterator<Item = &mut Tile> {
+        self.chunks
+            .values_mut()
+            .flat_map(|chunk| chunk.tiles.iter_mut())
+    }
+
+    /// chunks to keep loaded on each side of the player's, for a screen `view_size` cells
+    /// big. a locked scene can leave the player at the screen's edge, so this reaches a
+    /// whole screen away rather than half of one
+    pub fn load_radius(view_size: UVec2) -> IVec2 {
+        let cells = view_size.as_ivec2() + IVec2::splat(VIEW_SLACK);
+        ((cells + IVec2::splat(CHUNK_SIZE - 1)) / CHUNK_SIZE).max(IVec2::splat(MIN_LOAD_RADIUS))
+    }
+
+    /// generates the chunks around a position that a screen `view_size` cells big could show,
+    /// and drops the ones left far behind
+    pub fn stream(&mut self, center: IVec2, view_size: UVec2) {
+        let center = Self::chunk_of(center);
+        let radius = Self::load_radius(view_size);
+        let unload_radius = radius + IVec2::splat(UNLOAD_MARGIN);
+
+        self.chunks.retain(|coords, _| {
+            let distance = (*coords - center).abs();
+            distance.x <= unload_radius.x && distance.y <= unload_radius.y
+        });
+
+        for x in -radius.x..=radius.x {
+            for y in -radius.y..=radius.y {
+                let coords = center + IVec2::new(x, y);
+                if !self.chunks.contains_key(&coords) {
+                    let chunk = generate_chunk(self.seed, coords);
+                    self.chunks.insert(coords, chunk);
+                }
+            }
+        }
+    }
+}
+
+/// open grassland with the odd pond, stone outcrop and boulder. features are kept
+/// clear of the chunk's edges so no chunk ever depends on its neighbours
+fn generate_chunk(seed: u64, coords: IVec2) -> Chunk {
+    let mut rng = StdRng::seed_from_u64(chunk_seed(seed, coords));
+    let mut tiles = Vec::with_capacity((CHUNK_SIZE * CHUNK_SIZE) as usize);
+    for _ in 0..CHUNK_SIZE * CHUNK_SIZE {
+        tiles.push(if rng.random_bool(GRASS_CHANCE) {
+            Tile::new(TileType::GRASS)
+        } else {
+            Tile::empty()
+        });
+    }
+    let mut chunk = Chunk { tiles };
+
+    if rng.random_bool(POND_CHANCE) {
+        let radius = Vec2::new(rng.random_range(3.0..7.0), rng.random_range(2.0..4.0));
+        place_blob(&mut chunk, &mut rng, radius, TileType::WATER);
+    }
+    if rng.random_bool(OUTCROP_CHANCE) {
+        let radius = Vec2::new(rng.random_range(2.0..5.0), rng.random_range(1.5..3.0));
+        place_blob(&mut chunk, &mut rng, radius, TileType::STONE);
+    }
+    if rng.random_bool(BOULDER_CHANCE) {
+        let radius = Vec2::splat(rng.random_range(0.5..1.5));
+        place_blob(&mut chunk, &mut rng, radius, TileType::WALL);
+    }
+
+    chunk
+}
+
+/// fills an ellipse at a random spot inside the chunk
+fn place_blob(chunk: &mut Chunk, rng: &mut StdRng, radius: Vec2, tile_type: TileType) {
+    let margin = radius.ceil().as_ivec2() + IVec2::ONE;
+    let center = IVec2::new(
+        rng.random_range(margin.x..CHUNK_SIZE - margin.x),
+        rng.random_range(margin.y..CHUNK_SIZE - margin.y),
+    );
+
+    for x in center.x - margin.x..=center.x + margin.x {
+        for y in center.y - margin.y..=center.y + margin.y {
+            let offset = (IVec2::new(x, y) - center).as_vec2() / radius;
+            if offset.length_squared() <= 1.0 {
+                chunk.tiles[Chunk::index(IVec2::new(x, y))] = Tile::new(tile_type);
+            }
+        }
+    }
+}
+
+/// mixes the world seed with the chunk coordinates so neighbouring chunks don't look alike
+fn chunk_seed(seed: u64, coords: IVec2) -> u64 {
+    seed ^ (coords.x as u32 as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15)
+        ^ (coords.y as u32 as u64).wrapping_mul(0xc2b2_ae3d_27d4_eb4f)
+}
@@ -0,0 +1,362 @@
+use crate::maps::{
+    map::Map,
+    marker::{MapMarker, MarkerKind},
+    tile::{Tile, TileRegistry, TileType},
+};
+use bevy::prelude::*;
+use rand::{prelude::*, rngs::StdRng};
+
+/// leaves smaller than this on either axis aren't split any further
+const MIN_LEAF_SIZE: i32 = 12;
+
+/// smallest room interior we'll carve
+const MIN_ROOM_SIZE: i32 = 4;
+
+/// rooms that get an enemy spawn marker, counted from the room furthest from the player
+const ENEMY_SPAWN_ROOMS: usize = 4;
+
+/// chance a room other than the first holds a breakable
+const LOOT_CHANCE: f64 = 0.6;
+
+/// chance a room other than the first hides a trap
+const TRAP_CHANCE: f64 = 0.35;
+
+/// inclusive rectangle of floor cells
+#[derive(Debug, Clone, Copy)]
+struct Room {
+    min: IVec2,
+    max: IVec2,
+}
+
+impl Room {
+    fn center(&self) -> IVec2 {
+        (self.min + self.max) / 2
+    }
+
+    fn contains(&self, position: IVec2) -> bool {
+        position.x >= self.min.x
+            && position.x <= self.max.x
+            && position.y >= self.min.y
+            && position.y <= self.max.y
+    }
+}
+
+/// Generates a rooms-and-corridors dungeon by binary space partitioning.
+/// The same seed always produces the same layout.
+pub fn generate_dungeon(width: usize, height: usize, seed: u64, registry: TileRegistry) -> Map {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut map = Map::new(width, height, format!("Dungeon #{seed}"), registry);
+
+    for column in map.tiles.iter_mut() {
+        column.fill(Tile::wall());
+    }
+
+    // keep a solid wall around the edge of the map
+    let bounds = Room {
+        min: IVec2::ONE,
+        max: IVec2::new(width as i32 - 2, height as i32 - 2),
+    };
+
+    let mut rooms = Vec::new();
+    split(&mut map, &mut rng, bounds, &mut rooms);
+
+    if rooms.is_empty() {
+        return map;
+    }
+
+    place_doors(&mut map, &rooms);
+
+    // the player starts in the first room and the portal sits in the room furthest away on foot
+    let start = rooms[0].center();
+    let distances = map.walk_distances(start);
+    let mut by_distance: Vec<&Room> = rooms.iter().skip(1).collect();
+    by_distance.sort_by_key(|room| {
+        let distance = distances[map.cell_index(room.center())].unwrap_or_default();
+        std::cmp::Reverse(distance)
+    });
+
+    map.markers
+        .push(MapMarker::new(MarkerKind::PlayerStart, start));
+
+    if let Some(portal_room) = by_distance.first() {
+        map.markers
+            .push(MapMarker::new(MarkerKind::Portal, portal_room.center()));
+        lock_room(&mut map, &mut rng, portal_room, &rooms, start);
+    }
+
+    for room in by_distance.iter().skip(1).take(ENEMY_SPAWN_ROOMS) {
+        map.markers
+            .push(MapMarker::new(MarkerKind::EnemySpawn, room.center()));
+    }
+
+    // breakables sit along the top wall of some rooms and hidden traps along the bottom,
+    // clear of the markers at room centers
+    for room in rooms.iter().skip(1) {
+        if rng.random_bool(LOOT_CHANCE) {
+            let position = IVec2::new(rng.random_range(room.min.x..=room.max.x), room.max.y);
+            if position != room.center() {
+                map.markers.push(MapMarker::new(MarkerKind::Loot, position));
+            }
+        }
+        if rng.random_bool(TRAP_CHANCE) {
+            let position = IVec2::new(rng.random_range(room.min.x..=room.max.x), room.min.y);
+            if position != room.center() {
+                map.markers
+                    .push(MapMarker::new(MarkerKind::HiddenTrap, position));
+            }
+        }
+    }
+
+    map
+}
+
+/// recursively splits an area, carving a room in every leaf and joining siblings with a corridor.
+/// returns the center of a room inside the area, used to connect it to its sibling.
+fn split(map: &mut Map, rng: &mut StdRng, area: Room, rooms: &mut Vec<Room>) -> Option<IVec2> {
+    let size = area.max - area.min + IVec2::ONE;
+    let can_split_x = size.x >= MIN_LEAF_SIZE * 2;
+    let can_split_y = size.y >= MIN_LEAF_SIZE * 2;
+
+    if !can_split_x && !can_split_y {
+        return carve_room(map, rng, area, rooms);
+    }
+
+    // prefer cutting across the longer side so rooms don't end up as slivers
+    let split_vertically = match (can_split_x, can_split_y) {
+        (true, false) => true,
+        (false, true) => false,
+        _ => size.x >= size.y,
+    };
+
+    // both halves of a cut are at least `MIN_LEAF_SIZE` wide
+    let (first, second) = if split_vertically {
+        let cut = rng.random_range(area.min.x + MIN_LEAF_SIZE - 1..=area.max.x - MIN_LEAF_SIZE);
+        (
+            Room {
+                min: area.min,
+                max: IVec2::new(cut, area.max.y),
+            },
+            Room {
+                min: IVec2::new(cut + 1, area.min.y),
+                max: area.max,
+            },
+        )
+    } else {
+        let cut = rng.random_range(area.min.y + MIN_LEAF_SIZE - 1..=area.max.y - MIN_LEAF_SIZE);
+        (
+            Room {
+                min: area.min,
+                max: IVec2::new(area.max.x, cut),
+            },
+            Room {
+                min: IVec2::new(area.min.x, cut + 1),
+                max: area.max,
+            },
+        )
+    };
+
+    let first_center = split(map, rng, first, rooms);
+    let second_center = split(map, rng, second, rooms);
+
+    match (first_center, second_center) {
+        (Some(a), Some(b)) => {
+            carve_corridor(map, rng, a, b);
+            Some(if rng.random_bool(0.5) { a } else { b })
+        }
+        (center, None) | (None, center) => center,
+    }
+}
+
+fn carve_room(map: &mut Map, rng: &mut StdRng, leaf: Room, rooms: &mut Vec<Room>) -> Option<IVec2> {
+    // leave at least one wall between the room and its neighbours
+    let max_size = leaf.max - leaf.min - IVec2::ONE;
+    if max_size.x < MIN_ROOM_SIZE || max_size.y < MIN_ROOM_SIZE {
+        return None;
+    }
+
+    let room_size = IVec2::new(
+        rng.random_range(MIN_ROOM_SIZE..=max_size.x),
+        rng.random_range(MIN_ROOM_SIZE..=max_size.y),
+    );
+    let min = IVec2::new(
+        rng.random_range(leaf.min.x + 1..=leaf.max.x - room_size.x),
+        rng.random_range(leaf.min.y + 1..=leaf.max.y - room_size.y),
+    );
+    let room = Room {
+        min,
+        max: min + room_size - IVec2::ONE,
+    };
+
+    for x in room.min.x..=room.max.x {
+        for y in room.min.y..=room.max.y {
+            map.tiles[x as usize][y as usize] = Tile::new(TileType::STONE);
+        }
+    }
+
+    rooms.push(room);
+    Some(room.center())
+}
+
+/// L-shaped corridor, randomly going horizontal or vertical first
+pub(super) fn carve_corridor(map: &mut Map, rng: &mut StdRng, from: IVec2, to: IVec2) {
+    let corner = if rng.random_bool(0.5) {
+        IVec2::new(to.x, from.y)
+    } else {
+        IVec2::new(from.x, to.y)
+    };
+
+    for (start, end) in [(from, corner), (corner, to)] {
+        let step = (end - start).signum();
+        let mut position = start;
+        loop {
+            map.tiles[position.x as usize][position.y as usize] = Tile::new(TileType::STONE);
+            if position == end {
+                break;
+            }
+            position += step;
+        }
+    }
+}
+
+/// locks every door into a room and hides the key in the furthest room still reachable
+/// without it. rooms cut off by the lock, the locked one included, can't hold the key
+fn lock_room(map: &mut Map, rng: &mut StdRng, locked: &Room, rooms: &[Room], start: IVec2) {
+    let doors: Vec<IVec2> = (locked.min.x - 1..=locked.max.x + 1)
+        .flat_map(|x| (locked.min.y - 1..=locked.max.y + 1).map(move |y| IVec2::new(x, y)))
+        .filter(|position| {
+            map.get_tile(position.x, position.y)
+                .is_some_and(|tile| tile.tile_type == TileType::DOOR)
+        })
+        .collect();
+    if doors.is_empty() {
+        return;
+    }
+
+    let distances = map.walk_distances_avoiding(start, &doors);
+    let Some(key_room) = rooms
+        .iter()
+        .filter_map(|room| {
+            distances[map.cell_index(room.center())].map(|distance| (room, distance))
+        })
+        .max_by_key(|(_, distance)| *distance)
+        .map(|(room, _)| room)
+    else {
+        return;
+    };
+
+    for door in doors {
+        map.markers
+            .push(MapMarker::new(MarkerKind::LockedDoor, door));
+    }
+
+    // away from the center and the top and bottom rows, which hold the other markers
+    let key = IVec2::new(
+        rng.random_range(key_room.min.x..=key_room.max.x),
+        rng.random_range(key_room.min.y + 1..key_room.max.y),
+    );
+    let key = if key == key_room.center() {
+        key_room.min + IVec2::Y
+    } else {
+        key
+    };
+    map.markers.push(MapMarker::new(MarkerKind::Key, key));
+}
+
+/// a corridor cell right outside a room, squeezed between two walls, becomes a door
+fn place_doors(map: &mut Map, rooms: &[Room]) {
+    let in_room = |position: IVec2| rooms.iter().any(|room| room.contains(position));
+    let is_wall = |map: &Map, position: IVec2| {
+        map.get_tile(position.x, position.y)
+            .is_some_and(|tile| tile.tile_type == TileType::WALL)
+    };
+
+    let mut doors = Vec::new();
+    for x in 1..map.width as i32 - 1 {
+        for y in 1..map.height as i32 - 1 {
+            let position = IVec2::new(x, y);
+            if map.tiles[x as usize][y as usize].tile_type != TileType::STONE || in_room(position) {
+                continue;
+            }
+
+            for (along, across) in [(IVec2::X, IVec2::Y), (IVec2::Y, IVec2::X)] {
+                let enters_room = in_room(position + along) || in_room(position - along);
+                let walled_in = is_wall(map, position + across) && is_wall(map, position - across);
+                if enters_room && walled_in {
+                    doors.push(position);
+                    break;
+                }
+            }
+        }
+    }
+
+    for door in doors {
+        map.tiles[door.x as usize][door.y as usize] = Tile::new(TileType::DOOR);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maps::{
+        map::{GENERATED_HEIGHT, GENERATED_WIDTH},
+        tile::test_registry,
+    };
+
+    fn generate(seed: u64, registry: &TileRegistry) -> Map {
+        generate_dungeon(GENERATED_WIDTH, GENERATED_HEIGHT, seed, registry.clone())
+    }
+
+    #[test]
+    fn same_seed_same_layout() {
+        let registry = test_registry();
+        for seed in [0, 1, 42, 9001] {
+            let (first, second) = (generate(seed, &registry), generate(seed, &registry));
+            let tile_types = |map: &Map| {
+                map.tiles
+                    .iter()
+                    .flatten()
+                    .map(|tile| tile.tile_type)
+                    .collect::<Vec<_>>()
+            };
+            let markers = |map: &Map| {
+                map.markers
+                    .iter()
+                    .map(|marker| (marker.kind, marker.position))
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(tile_types(&first), tile_types(&second), "seed {seed}");
+            assert_eq!(markers(&first), markers(&second), "seed {seed}");
+        }
+    }
+
+    #[test]
+    fn portal_and_every_floor_cell_reachable() {
+        let registry = test_registry();
+        for seed in 0..200 {
+            let map = generate(seed, &registry);
+            let start = map
+                .marker(MarkerKind::PlayerStart)
+                .unwrap_or_else(|| panic!("seed {seed} has no player start"));
+            let portal = map
+                .marker(MarkerKind::Portal)
+                .unwrap_or_else(|| panic!("seed {seed} has no portal"));
+            let distances = map.walk_distances(start);
+
+            assert!(
+                distances[map.cell_index(portal)].is_some(),
+                "seed {seed}: portal at {portal} is unreachable"
+            );
+            for x in 0..map.width as i32 {
+                for y in 0..map.height as i32 {
+                    let position = IVec2::new(x, y);
+                    if map.is_walkable(x, y) {
+                        assert!(
+                            distances[map.cell_index(position)].is_some(),
+                            "seed {seed}: {position} is walkable but unreachable"
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
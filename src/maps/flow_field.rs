@@ -0,0 +1,128 @@
+use crate::maps::map::Map;
+use bevy::prelude::*;
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+/// cost of a straight step, diagonals cost roughly `sqrt(2)` times as much
+const STRAIGHT_COST: u32 = 2;
+const DIAGONAL_COST: u32 = 3;
+
+/// how far the field reaches from its target on maps with endless terrain
+const ENDLESS_RADIUS: i32 = 48;
+
+const NEIGHBOURS: [IVec2; 8] = [
+    IVec2::new(1, 0),
+    IVec2::new(-1, 0),
+    IVec2::new(0, 1),
+    IVec2::new(0, -1),
+    IVec2::new(1, 1),
+    IVec2::new(1, -1),
+    IVec2::new(-1, 1),
+    IVec2::new(-1, -1),
+];
+
+/// Dijkstra map towards a single target, shared by every enemy.
+/// Each cell stores the step to take next, so following it is a single lookup.
+#[derive(Resource, Default)]
+pub struct FlowField {
+    pub target: Option<IVec2>,
+    /// world position of the field's first cell
+    origin: IVec2,
+    width: usize,
+    height: usize,
+    costs: Vec<Option<u32>>,
+    steps: Vec<IVec2>,
+}
+
+impl FlowField {
+    pub fn build(map: &Map, target: IVec2) -> Self {
+        // endless maps only get a window around the target, everything else is covered whole
+        let (origin, width, height) = if map.terrain.is_some() {
+            let size = (ENDLESS_RADIUS * 2 + 1) as usize;
+            (target - IVec2::splat(ENDLESS_RADIUS), size, size)
+        } else {
+            (IVec2::ZERO, map.width, map.height)
+        };
+
+        let mut field = Self {
+            target: Some(target),
+            origin,
+            width,
+            height,
+            costs: vec![None; width * height],
+            steps: vec![IVec2::ZERO; width * height],
+        };
+
+        let Some(target_index) = field.index(target) else {
+            return field;
+        };
+
+        let mut queue = BinaryHeap::new();
+        field.costs[target_index] = Some(0);
+        queue.push(Reverse((0, target.x, target.y)));
+
+        while let Some(Reverse((cost, x, y))) = queue.pop() {
+            let position = IVec2::new(x, y);
+            if field.cost(position).is_some_and(|best| best < cost) {
+                continue;
+            }
+
+            for offset in NEIGHBOURS {
+                let next = position + offset;
+                let Some(index) = field.index(next) else {
+                    continue;
+                };
+                if !can_step(map, next, -offset) {
+                    continue;
+                }
+
+                // the step is taken from `next` back onto `position`, so that's the tile walked on
+                let step_cost = if offset.x != 0 && offset.y != 0 {
+                    DIAGONAL_COST
+                } else {
+                    STRAIGHT_COST
+                } * map.movement_cost(position.x, position.y);
+                let next_cost = cost + step_cost;
+                if field.costs[index].is_none_or(|best| next_cost < best) {
+                    field.costs[index] = Some(next_cost);
+                    // walking back along the way we came leads to the target
+                    field.steps[index] = -offset;
+                    queue.push(Reverse((next_cost, next.x, next.y)));
+                }
+            }
+        }
+
+        field
+    }
+
+    /// the step to take from a cell, `None` off the field or where the target can't be reached
+    pub fn step(&self, position: IVec2) -> Option<IVec2> {
+        let index = self.index(position)?;
+        self.costs[index]?;
+        Some(self.steps[index]).filter(|step| *step != IVec2::ZERO)
+    }
+
+    fn cost(&self, position: IVec2) -> Option<u32> {
+        self.index(position).and_then(|index| self.costs[index])
+    }
+
+    fn index(&self, position: IVec2) -> Option<usize> {
+        let local = position - self.origin;
+        let in_bounds = local.x >= 0
+            && local.y >= 0
+            && (local.x as usize) < self.width
+            && (local.y as usize) < self.height;
+        in_bounds.then(|| local.x as usize * self.height + local.y as usize)
+    }
+}
+
+/// whether something standing on `from` can step by `step`, diagonals can't cut wall corners
+fn can_step(map: &Map, from: IVec2, step: IVec2) -> bool {
+    let walkable = |position: IVec2| map.is_walkable(position.x, position.y);
+    if !walkable(from) || !walkable(from + step) {
+        return false;
+    }
+
+    step.x == 0
+        || step.y == 0
+        || (walkable(from + IVec2::new(step.x, 0)) && walkable(from + IVec2::new(0, step.y)))
+}
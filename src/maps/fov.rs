@@ -0,0 +1,125 @@
+use crate::maps::map::Map;
+use bevy::prelude::*;
+
+/// how far the player can see in an unobstructed direction
+pub const SIGHT_RADIUS: i32 = 30;
+
+/// Recomputes which tiles are visible from `origin` with symmetric shadowcasting,
+/// marking everything seen as explored. If a tile can see another, the reverse holds too.
+pub fn compute_fov(map: &mut Map, origin: IVec2, radius: i32) {
+    for tile in map.tiles_mut() {
+        tile.visible = false;
+    }
+
+    reveal(map, origin);
+
+    for quadrant in [IVec2::Y, IVec2::NEG_Y, IVec2::X, IVec2::NEG_X] {
+        let first_row = Row {
+            depth: 1,
+            start: Slope::new(-1, 1),
+            end: Slope::new(1, 1),
+        };
+        scan(map, origin, quadrant, radius, first_row);
+    }
+}
+
+/// exact fraction, shadowcasting is only symmetric when slopes aren't rounded
+#[derive(Debug, Clone, Copy)]
+struct Slope {
+    rise: i32,
+    run: i32,
+}
+
+impl Slope {
+    fn new(rise: i32, run: i32) -> Self {
+        Self { rise, run }
+    }
+
+    /// slope of the edge of a tile nearest the start of its row
+    fn of_tile_edge(depth: i32, column: i32) -> Self {
+        Self::new(2 * column - 1, 2 * depth)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Row {
+    depth: i32,
+    start: Slope,
+    end: Slope,
+}
+
+impl Row {
+    /// first and last column of the row, ties round towards the middle of the sector
+    fn columns(&self) -> std::ops::RangeInclusive<i32> {
+        let min =
+            (2 * self.depth * self.start.rise + self.start.run).div_euclid(2 * self.start.run);
+        let max = -(self.end.run - 2 * self.depth * self.end.rise).div_euclid(2 * self.end.run);
+        min..=max
+    }
+
+    /// floor tiles are only lit when their center lies inside the sector
+    fn is_symmetric(&self, column: i32) -> bool {
+        column * self.start.run >= self.depth * self.start.rise
+            && column * self.end.run <= self.depth * self.end.rise
+    }
+
+    fn next(&self) -> Self {
+        Self {
+            depth: self.depth + 1,
+            ..*self
+        }
+    }
+}
+
+fn scan(map: &mut Map, origin: IVec2, quadrant: IVec2, radius: i32, mut row: Row) {
+    if row.depth > radius {
+        return;
+    }
+
+    let mut previous_opaque = None;
+    for column in row.columns() {
+        let position = transform(origin, quadrant, row.depth, column);
+        let opaque = is_opaque(map, position);
+        let in_range = row.depth * row.depth + column * column <= radius * radius;
+
+        if in_range && (opaque || row.is_symmetric(column)) {
+            reveal(map, position);
+        }
+
+        match (previous_opaque, opaque) {
+            // leaving a wall, the sector narrows from the start
+            (Some(true), false) => row.start = Slope::of_tile_edge(row.depth, column),
+            // hitting a wall, everything before it keeps going on the next row
+            (Some(false), true) => {
+                let mut next = row.next();
+                next.end = Slope::of_tile_edge(row.depth, column);
+                scan(map, origin, quadrant, radius, next);
+            }
+            _ => {}
+        }
+        previous_opaque = Some(opaque);
+    }
+
+    if previous_opaque == Some(false) {
+        scan(map, origin, quadrant, radius, row.next());
+    }
+}
+
+/// rows run away from the origin along `quadrant`, columns run across it
+fn transform(origin: IVec2, quadrant: IVec2, depth: i32, column: i32) -> IVec2 {
+    let across = IVec2::new(quadrant.y, quadrant.x);
+    origin + quadrant * depth + across * column
+}
+
+/// anything outside the map blocks sight
+fn is_opaque(map: &Map, position: IVec2) -> bool {
+    map.get_tile(position.x, position.y)
+        .is_none_or(|tile| map.tile_def(tile.tile_type).blocks_light)
+}
+
+fn reveal(map: &mut Map, position: IVec2) {
+    if let Some(tile) = map.get_tile_mut(position.x, position.y) {
+        tile.visible = true;
+        tile.explored = true;
+    }
+}
@@ -0,0 +1,168 @@
+use crate::{
+    maps::{map::Map, tile::TileRegistry},
+    resources::Level,
+};
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadState, io::Reader},
+    prelude::*,
+};
+use std::collections::HashMap;
+
+pub struct MapLoaderPlugin;
+
+impl Plugin for MapLoaderPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<Map>()
+            .init_asset::<TileRegistry>()
+            .init_asset_loader::<XpMapLoader>()
+            .init_asset_loader::<TileRegistryLoader>()
+            .init_resource::<MapHandles>()
+            .add_systems(Startup, (load_tile_registry, load_level_maps))
+            .add_systems(
+                Update,
+                insert_tile_registry.run_if(not(resource_exists::<TileRegistry>)),
+            );
+    }
+}
+
+/// Reads REXPaint `.xp` files into a [`Map`].
+#[derive(Default)]
+pub struct XpMapLoader;
+
+impl AssetLoader for XpMapLoader {
+    type Asset = Map;
+    type Settings = ();
+    type Error = anyhow::Error;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        let name = load_context
+            .path()
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        // maps are read against the tile set, so it's loaded first rather than as a handle
+        let registry = load_context
+            .loader()
+            .immediate()
+            .load::<TileRegistry>(TileRegistry::PATH)
+            .await?;
+
+        Map::from_xp_data(&bytes, name, registry.get().clone())
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["xp"]
+    }
+}
+
+/// Reads the tile set from `tiles.ron` into a [`TileRegistry`].
+#[derive(Default)]
+pub struct TileRegistryLoader;
+
+impl AssetLoader for TileRegistryLoader {
+    type Asset = TileRegistry;
+    type Settings = ();
+    type Error = anyhow::Error;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        TileRegistry::from_ron(std::str::from_utf8(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["tiles.ron"]
+    }
+}
+
+/// Handle to the tile set, copied into the [`TileRegistry`] resource once it's loaded.
+#[derive(Resource)]
+pub struct TileRegistryHandle(pub Handle<TileRegistry>);
+
+pub fn load_tile_registry(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(TileRegistryHandle(asset_server.load(TileRegistry::PATH)));
+}
+
+/// Makes the tile set a resource for the generators and editor as soon as it's in.
+fn insert_tile_registry(
+    mut commands: Commands,
+    handle: Option<Res<TileRegistryHandle>>,
+    registries: Res<Assets<TileRegistry>>,
+) {
+    if let Some(registry) = handle.and_then(|handle| registries.get(&handle.0)) {
+        commands.insert_resource(registry.clone());
+    }
+}
+
+/// Handles to every level's map, requested at startup so they're ready before `GameState::Game`.
+#[derive(Resource, Default)]
+pub struct MapHandles(pub HashMap<Level, Handle<Map>>);
+
+impl MapHandles {
+    pub fn get(&self, level: Level) -> Option<&Handle<Map>> {
+        self.0.get(&level)
+    }
+
+    pub fn all_loaded(&self, asset_server: &AssetServer) -> bool {
+        self.0
+            .values()
+            .all(|handle| asset_server.is_loaded_with_dependencies(handle))
+    }
+}
+
+pub fn load_level_maps(asset_server: Res<AssetServer>, mut map_handles: ResMut<MapHandles>) {
+    for level in Level::ALL {
+        if let Some(path) = level.map_path() {
+            map_handles.0.insert(level, asset_server.load(path));
+        }
+    }
+}
+
+/// Any map or the tile set failing to load is fatal, there's no sensible level to fall back to.
+pub fn report_map_load_failures(
+    asset_server: Res<AssetServer>,
+    map_handles: Res<MapHandles>,
+    tile_registry: Res<TileRegistryHandle>,
+    mut app_exit: EventWriter<AppExit>,
+) {
+    let mut failed = false;
+
+    if let LoadState::Failed(error) = asset_server.load_state(&tile_registry.0) {
+        error!(
+            "failed to load the tiles from 'assets/{}': {}",
+            TileRegistry::PATH,
+            error
+        );
+        failed = true;
+    }
+
+    for (level, handle) in map_handles.0.iter() {
+        if let LoadState::Failed(error) = asset_server.load_state(handle) {
+            error!(
+                "failed to load the {:?} map from 'assets/{}': {}",
+                level,
+                level.map_path().unwrap_or_default(),
+                error
+            );
+            failed = true;
+        }
+    }
+
+    if failed {
+        app_exit.write(AppExit::error());
+    }
+}
@@ -1,64 +1,234 @@
-use crate::{resources::Level, maps::tile::{Tile, TileType}};
+use crate::{
+    maps::{
+        cave::generate_cave,
+        chunk::ChunkedTerrain,
+        dungeon::generate_dungeon,
+        loader::MapHandles,
+        marker::{MapMarker, MarkerKind},
+        tile::{Stairs, Tile, TileDef, TileRegistry, TileType},
+        zone::{SpawnCategory, Zone, ZoneKind},
+    },
+    resources::{Level, MapSeed},
+};
+use anyhow::{anyhow, bail};
 use bevy::prelude::*;
 use rexpaint::*;
-use std::io::Cursor;
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    io::Cursor,
+};
 
-#[derive(Resource)]
+/// size of procedurally generated levels
+pub(crate) const GENERATED_WIDTH: usize = 80;
+pub(crate) const GENERATED_HEIGHT: usize = 50;
+
+#[derive(Resource, Asset, TypePath, Clone)]
 pub struct Map {
     pub width: usize,
     pub height: usize,
     pub tiles: Vec<Vec<Tile>>,
     pub name: String,
+    pub markers: Vec<MapMarker>,
+    pub zones: Vec<Zone>,
+    /// endless terrain beyond the edges of `tiles`, for levels that never end
+    pub terrain: Option<ChunkedTerrain>,
+    /// what every tile type on the map looks like and how it behaves
+    pub registry: TileRegistry,
 }
 
 impl Map {
-    pub fn new(width: usize, height: usize, name: String) -> Self {
+    pub fn new(width: usize, height: usize, name: String, registry: TileRegistry) -> Self {
         let tiles = vec![vec![Tile::empty(); height]; width];
         Self {
             width,
             height,
             tiles,
             name,
+            markers: Vec::new(),
+            zones: Vec::new(),
+            terrain: None,
+            registry,
         }
     }
 
-    pub fn from_xp_data(xp_data: &[u8], name: String) -> Result<Self, Box<dyn std::error::Error>> {
+    pub fn from_xp_data(
+        xp_data: &[u8],
+        name: String,
+        registry: TileRegistry,
+    ) -> anyhow::Result<Self> {
         let mut cursor = Cursor::new(xp_data);
-        let xp_file = XpFile::read(&mut cursor)?;
+        let xp_file = XpFile::read(&mut cursor)
+            .map_err(|error| anyhow!("not a valid REXPaint file ({error})"))?;
 
-        if xp_file.layers.is_empty() {
-            return Err("XP file has no layers".into());
-        }
+        // layer 0 holds tiles, layer 1 spawn markers and layer 2 zones
+        let Some(layer) = xp_file.layers.first() else {
+            bail!("REXPaint file has no layers");
+        };
 
-        // Use the first layer for our map
-        let layer = &xp_file.layers[0];
         let width = layer.width;
         let height = layer.height;
+        if width == 0 || height == 0 {
+            bail!("REXPaint layer is empty ({}x{})", width, height);
+        }
+
+        for (index, other) in xp_file.layers.iter().enumerate().skip(1) {
+            if other.width != width || other.height != height {
+                bail!(
+                    "layer {} is {}x{} but the tile layer is {}x{}",
+                    index,
+                    other.width,
+                    other.height,
+                    width,
+                    height
+                );
+            }
+        }
+
+        let mut map = Map::new(width, height, name, registry);
 
-        let mut map = Map::new(width, height, name);
+        for (x, y, ch) in Self::layer_glyphs(layer) {
+            map.tiles[x as usize][y as usize] = Tile::new(map.char_to_tile_type(ch));
+        }
+
+        if let Some(marker_layer) = xp_file.layers.get(1) {
+            for (x, y, ch) in Self::layer_glyphs(marker_layer) {
+                match MarkerKind::from_char(ch) {
+                    Some(kind) => map.markers.push(MapMarker::new(kind, IVec2::new(x, y))),
+                    None => warn!("unknown spawn marker '{}' at ({}, {})", ch, x, y),
+                }
+            }
+        }
 
-        // convert XP cells to our tile format
-        for x in 0..width {
-            for y in 0..height {
-                if let Some(cell) = layer.get(x, y) {
-                    let tile_type = Self::char_to_tile_type(cell.ch as u8 as char);
-                    map.tiles[x][y] = Tile::new(tile_type);
+        if let Some(zone_layer) = xp_file.layers.get(2) {
+            let mut painted = HashMap::new();
+            for (x, y, ch) in Self::layer_glyphs(zone_layer) {
+                match ZoneKind::from_char(ch) {
+                    Some(kind) => {
+                        painted.insert(IVec2::new(x, y), kind);
+                    }
+                    None => warn!("unknown zone '{}' at ({}, {})", ch, x, y),
                 }
             }
+            map.zones = Self::group_zones(painted);
         }
 
         Ok(map)
     }
 
-    fn char_to_tile_type(ch: char) -> TileType {
-        match ch {
-            '#' => TileType::Wall,
-            '.' => TileType::Grass,
-            '~' => TileType::Water,
-            ':' => TileType::Stone,
-            '+' => TileType::Door,
-            _ => TileType::Empty,
+    /// writes the map back out as the three layers `from_xp_data` reads,
+    /// the endless terrain isn't saved
+    pub fn to_xp(&self) -> XpFile {
+        let mut xp_file = XpFile::new(self.width, self.height);
+        let mut marker_layer = XpLayer::new(self.width, self.height);
+        let mut zone_layer = XpLayer::new(self.width, self.height);
+
+        // unpainted cells of the upper layers let the tiles show through in REXPaint
+        for cell in marker_layer
+            .cells
+            .iter_mut()
+            .chain(zone_layer.cells.iter_mut())
+        {
+            cell.bg = XpColor::TRANSPARENT;
+        }
+
+        for x in 0..self.width as i32 {
+            for y in 0..self.height as i32 {
+                let tile_type = self.tiles[x as usize][y as usize].tile_type;
+                if let Some(cell) = Self::xp_cell(&mut xp_file.layers[0], IVec2::new(x, y)) {
+                    let def = self.registry.def(tile_type);
+                    cell.ch = def.glyph as u32;
+                    cell.fg = Self::xp_color(def.color());
+                }
+            }
+        }
+
+        for marker in &self.markers {
+            if let Some(cell) = Self::xp_cell(&mut marker_layer, marker.position) {
+                cell.ch = marker.kind.to_char() as u32;
+                cell.fg = XpColor::new(255, 255, 0);
+                cell.bg = XpColor::BLACK;
+            }
+        }
+
+        for zone in &self.zones {
+            for position in zone.cells() {
+                if let Some(cell) = Self::xp_cell(&mut zone_layer, position) {
+                    cell.ch = zone.kind.to_char() as u32;
+                    cell.fg = XpColor::new(255, 255, 255);
+                    cell.bg = XpColor::BLACK;
+                }
+            }
+        }
+
+        xp_file.layers.push(marker_layer);
+        xp_file.layers.push(zone_layer);
+        xp_file
+    }
+
+    /// the cell of a layer at a world position, the inverse of `layer_glyphs`
+    fn xp_cell(layer: &mut XpLayer, position: IVec2) -> Option<&mut XpCell> {
+        let row = layer.height as i32 - 1 - position.y;
+        if position.x < 0 || row < 0 {
+            return None;
+        }
+        layer.get_mut(position.x as usize, row as usize)
+    }
+
+    fn xp_color(color: Color) -> XpColor {
+        let [r, g, b, _] = color.to_srgba().to_u8_array();
+        XpColor::new(r, g, b)
+    }
+
+    /// every painted cell of a layer in world coordinates,
+    /// REXPaint rows run top to bottom while our world's y axis points up
+    pub fn layer_glyphs(layer: &XpLayer) -> impl Iterator<Item = (i32, i32, char)> + '_ {
+        (0..layer.width).flat_map(move |x| {
+            (0..layer.height).filter_map(move |y| {
+                let cell = layer.get(x, y)?;
+                let ch = cell.ch as u8 as char;
+                if cell.bg.is_transparent() || ch == ' ' || ch == '\0' {
+                    return None;
+                }
+
+                Some((x as i32, (layer.height - 1 - y) as i32, ch))
+            })
+        })
+    }
+
+    /// splits painted zone cells into one zone per connected area of the same kind
+    fn group_zones(mut painted: HashMap<IVec2, ZoneKind>) -> Vec<Zone> {
+        let mut zones = Vec::new();
+
+        while let Some((&seed, &kind)) = painted.iter().next() {
+            let mut cells = HashSet::new();
+            let mut frontier = vec![seed];
+            painted.remove(&seed);
+
+            while let Some(cell) = frontier.pop() {
+                cells.insert(cell);
+                for offset in [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y] {
+                    let neighbour = cell + offset;
+                    if painted.get(&neighbour) == Some(&kind) {
+                        painted.remove(&neighbour);
+                        frontier.push(neighbour);
+                    }
+                }
+            }
+
+            zones.push(Zone::from_cells(kind, cells));
         }
+
+        zones
+    }
+
+    /// unknown glyphs are left empty, `mapcheck` reports them
+    fn char_to_tile_type(&self, ch: char) -> TileType {
+        self.registry.from_char(ch).unwrap_or(TileType::EMPTY)
+    }
+
+    /// what a tile type looks like and how it behaves
+    pub fn tile_def(&self, tile_type: TileType) -> &TileDef {
+        self.registry.def(tile_type)
     }
 
     pub fn in_bounds(&self, x: i32, y: i32) -> bool {
@@ -66,16 +236,40 @@ impl Map {
     }
 
     pub fn is_walkable(&self, x: i32, y: i32) -> bool {
-        if !self.in_bounds(x, y) {
-            return false;
-        }
+        self.get_tile(x, y)
+            .is_some_and(|tile| self.tile_def(tile.tile_type).walkable)
+    }
 
-        self.tiles[x as usize][y as usize].tile_type.is_walkable()
+    /// how much walking onto a cell costs, relative to open ground
+    pub fn movement_cost(&self, x: i32, y: i32) -> u32 {
+        self.get_tile(x, y)
+            .map_or(1, |tile| self.tile_def(tile.tile_type).movement_cost)
     }
 
+    /// whether the player can currently see a cell, as of the last field of view update
+    pub fn is_visible(&self, position: IVec2) -> bool {
+        self.get_tile(position.x, position.y)
+            .is_some_and(|tile| tile.visible)
+    }
+
+    /// cells outside the map never stop a projectile, it just flies off
+    pub fn blocks_projectiles(&self, x: i32, y: i32) -> bool {
+        self.get_tile(x, y)
+            .is_some_and(|tile| self.tile_def(tile.tile_type).blocks_projectiles)
+    }
+
+    /// whether a projectile can travel in a straight line between two cells
+    pub fn projectile_path_clear(&self, from: IVec2, to: IVec2) -> bool {
+        line(from, to).all(|cell| !self.blocks_projectiles(cell.x, cell.y))
+    }
+
+    /// tiles outside the map come from its endless terrain, if it has any loaded there
     pub fn get_tile(&self, x: i32, y: i32) -> Option<&Tile> {
         if !self.in_bounds(x, y) {
-            return None;
+            return self
+                .terrain
+                .as_ref()
+                .and_then(|terrain| terrain.get_tile(IVec2::new(x, y)));
         }
 
         Some(&self.tiles[x as usize][y as usize])
@@ -83,69 +277,260 @@ impl Map {
 
     pub fn get_tile_mut(&mut self, x: i32, y: i32) -> Option<&mut Tile> {
         if !self.in_bounds(x, y) {
-            return None;
+            return self
+                .terrain
+                .as_mut()
+                .and_then(|terrain| terrain.get_tile_mut(IVec2::new(x, y)));
         }
 
         Some(&mut self.tiles[x as usize][y as usize])
     }
-}
 
-pub fn load_map_system(mut commands: Commands, level: Res<Level>) {
-    // For now, we'll create a simple test map
-    // In the future, we'll load actual rexpaint files based on the level
-    let map = create_test_map_for_level(*level);
-    commands.insert_resource(map);
-}
+    /// every tile of the map and of its loaded terrain
+    pub fn tiles_mut(&mut self) -> impl Iterator<Item = &mut Tile> {
+        let terrain_tiles = self
+            .terrain
+            .iter_mut()
+            .flat_map(|terrain| terrain.tiles_mut());
+        self.tiles.iter_mut().flatten().chain(terrain_tiles)
+    }
 
-fn create_test_map_for_level(level: Level) -> Map {
-    let map_name = match level {
-        Level::Grassland => "Grassland",
-        Level::Dungeon => "Dungeon",
-        Level::Rest => "Rest Area",
-        Level::Survival => "Survival Mode",
-    };
+    /// flat index of a cell, used by per-cell buffers such as `walk_distances`
+    pub fn cell_index(&self, position: IVec2) -> usize {
+        position.x as usize * self.height + position.y as usize
+    }
 
-    let mut map = Map::new(80, 50, map_name.to_string());
+    /// breadth-first walking distance from a cell to every walkable cell of `tiles`.
+    /// closed doors count as passable since they can be opened.
+    /// `None` where unreachable, the endless terrain isn't searched
+    pub fn walk_distances(&self, from: IVec2) -> Vec<Option<u32>> {
+        self.walk_distances_avoiding(from, &[])
+    }
 
-    match level {
-        Level::Grassland => {
-            for x in 10..70 {
-                for y in 10..40 {
-                    if x % 3 == 0 && y % 3 == 0 {
-                        map.tiles[x][y] = Tile::new(TileType::Grass);
-                    }
+    /// like `walk_distances`, but never stepping onto the `blocked` cells, such as locked doors
+    pub fn walk_distances_avoiding(&self, from: IVec2, blocked: &[IVec2]) -> Vec<Option<u32>> {
+        let mut distances = vec![None; self.width * self.height];
+        if !self.in_bounds(from.x, from.y) {
+            return distances;
+        }
+
+        let mut queue = VecDeque::new();
+        distances[self.cell_index(from)] = Some(0);
+        queue.push_back(from);
+
+        while let Some(position) = queue.pop_front() {
+            let distance = distances[self.cell_index(position)].unwrap_or_default();
+            for offset in [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y] {
+                let next = position + offset;
+                let open = self.in_bounds(next.x, next.y)
+                    && !blocked.contains(&next)
+                    && self.get_tile(next.x, next.y).is_some_and(|tile| {
+                        self.tile_def(tile.tile_type).walkable || tile.tile_type.is_door()
+                    });
+                if open && distances[self.cell_index(next)].is_none() {
+                    distances[self.cell_index(next)] = Some(distance + 1);
+                    queue.push_back(next);
                 }
             }
         }
-        Level::Dungeon => {
-            for x in 25..55 {
-                map.tiles[x][25] = Tile::wall();
+
+        distances
+    }
+
+    /// places a marker on a cell, replacing whatever marker was there, or clears it with `None`
+    pub fn set_marker(&mut self, position: IVec2, kind: Option<MarkerKind>) {
+        self.markers.retain(|marker| marker.position != position);
+        if let Some(kind) = kind {
+            self.markers.push(MapMarker::new(kind, position));
+        }
+    }
+
+    /// paints a single cell of a zone, or clears any zone from it with `None`.
+    /// zones are regrouped afterwards, so painting can join or split them
+    pub fn set_zone(&mut self, position: IVec2, kind: Option<ZoneKind>) {
+        let mut painted: HashMap<IVec2, ZoneKind> = self
+            .zones
+            .iter()
+            .flat_map(|zone| zone.cells().into_iter().map(|cell| (cell, zone.kind)))
+            .collect();
+
+        match kind {
+            Some(kind) => painted.insert(position, kind),
+            None => painted.remove(&position),
+        };
+        self.zones = Self::group_zones(painted);
+    }
+
+    /// every floor but the last has stairs down in place of its portal, and every floor but
+    /// the first has stairs back up where the player starts
+    pub fn add_stairs(&mut self, floor: usize, floor_count: usize) {
+        if floor + 1 < floor_count
+            && let Some(portal) = self.marker(MarkerKind::Portal)
+        {
+            self.markers
+                .retain(|marker| marker.kind != MarkerKind::Portal);
+            if let Some(tile) = self.get_tile_mut(portal.x, portal.y) {
+                tile.tile_type = TileType::STAIRS_DOWN;
             }
-            for y in 20..30 {
-                map.tiles[30][y] = Tile::wall();
-                map.tiles[50][y] = Tile::wall();
+        }
+
+        if floor > 0
+            && let Some(start) = self.marker(MarkerKind::PlayerStart)
+            && let Some(tile) = self.get_tile_mut(start.x, start.y)
+        {
+            tile.tile_type = TileType::STAIRS_UP;
+        }
+    }
+
+    /// the first stairs leading one way
+    pub fn find_stairs(&self, direction: Stairs) -> Option<IVec2> {
+        (0..self.width as i32)
+            .flat_map(|x| (0..self.height as i32).map(move |y| IVec2::new(x, y)))
+            .find(|position| {
+                let tile_type = self.tiles[position.x as usize][position.y as usize].tile_type;
+                self.tile_def(tile_type).stairs == Some(direction)
+            })
+    }
+
+    /// position of the first marker of a kind
+    pub fn marker(&self, kind: MarkerKind) -> Option<IVec2> {
+        self.markers
+            .iter()
+            .find(|marker| marker.kind == kind)
+            .map(|marker| marker.position)
+    }
+
+    /// every zone covering a position, zones may overlap
+    pub fn zones_at(&self, position: IVec2) -> impl Iterator<Item = &Zone> {
+        self.zones
+            .iter()
+            .filter(move |zone| zone.contains(position))
+    }
+
+    pub fn is_safe(&self, position: IVec2) -> bool {
+        self.zones_at(position)
+            .any(|zone| zone.kind == ZoneKind::SafeZone)
+    }
+
+    /// nothing spawns in safe zones, and spawn zones only accept their own categories
+    pub fn allows_spawn(&self, position: IVec2, category: SpawnCategory) -> bool {
+        if self.is_safe(position) {
+            return false;
+        }
+
+        let mut in_spawn_zone = false;
+        for zone in self.zones_at(position) {
+            if let ZoneKind::SpawnZone(allowed) = zone.kind {
+                if allowed == category {
+                    return true;
+                }
+                in_spawn_zone = true;
             }
         }
-        Level::Rest => {
-            // campfire in the center
-            map.tiles[40][25] = Tile::new(TileType::Empty);
+
+        !in_spawn_zone
+    }
+}
+
+/// picks the step to take towards a direction. when a diagonal is blocked
+/// we slide along whichever of its axes is open, so nothing snags on corners
+pub fn slide_step(step: IVec2, mut is_open: impl FnMut(IVec2) -> bool) -> Option<IVec2> {
+    [step, IVec2::new(step.x, 0), IVec2::new(0, step.y)]
+        .into_iter()
+        .filter(|&candidate| candidate != IVec2::ZERO)
+        .find(|&candidate| is_open(candidate))
+}
+
+/// every cell on a straight line between two cells, both ends included
+pub fn line(from: IVec2, to: IVec2) -> impl Iterator<Item = IVec2> {
+    let delta = (to - from).abs();
+    let step = (to - from).signum();
+    let mut position = from;
+    let mut error = delta.x - delta.y;
+    let mut done = false;
+
+    std::iter::from_fn(move || {
+        if done {
+            return None;
         }
-        Level::Survival => {
-            map.tiles[40][20] = Tile::new(TileType::Empty);
-            map.tiles[41][20] = Tile::new(TileType::Wall);
-            map.tiles[42][20] = Tile::new(TileType::Water);
-            map.tiles[43][20] = Tile::new(TileType::Grass);
-            map.tiles[44][20] = Tile::new(TileType::Stone);
-            map.tiles[45][20] = Tile::new(TileType::Door);
+
+        let current = position;
+        if position == to {
+            done = true;
+        } else {
+            let doubled = error * 2;
+            if doubled > -delta.y {
+                error -= delta.y;
+                position.x += step.x;
+            }
+            if doubled < delta.x {
+                error += delta.x;
+                position.y += step.y;
+            }
         }
+        Some(current)
+    })
+}
+
+/// A map being play-tested from the map editor, loaded in place of every level's own map.
+#[derive(Resource)]
+pub struct PlaytestMap(pub Map);
+
+/// swaps in the first floor of the current level, the handles were loaded during
+/// `GameState::Loading`
+pub fn load_map_system(
+    mut commands: Commands,
+    level: Res<Level>,
+    map_handles: Res<MapHandles>,
+    maps: Res<Assets<Map>>,
+    map_seed: Res<MapSeed>,
+    registry: Res<TileRegistry>,
+    playtest_map: Option<Res<PlaytestMap>>,
+) {
+    if let Some(playtest_map) = playtest_map {
+        commands.insert_resource(playtest_map.0.clone());
+        return;
     }
 
-    // mark all tiles as explored for testing
-    for x in 0..map.width {
-        for y in 0..map.height {
-            map.tiles[x][y].explored = true;
+    if let Some(map) = build_floor_map(*level, 0, &map_handles, &maps, &registry, map_seed.0) {
+        commands.insert_resource(map);
+    }
+}
+
+/// a fresh map for one floor of a level, with its stairs in place
+pub fn build_floor_map(
+    level: Level,
+    floor: usize,
+    map_handles: &MapHandles,
+    maps: &Assets<Map>,
+    registry: &TileRegistry,
+    seed: u64,
+) -> Option<Map> {
+    // every floor of a generated level gets its own layout
+    let seed = seed.wrapping_add(floor as u64);
+    let mut map = match level {
+        Level::Dungeon => {
+            info!("generating dungeon floor {} with seed {}", floor, seed);
+            generate_dungeon(GENERATED_WIDTH, GENERATED_HEIGHT, seed, registry.clone())
+        }
+        Level::Cave => {
+            info!("generating cave floor {} with seed {}", floor, seed);
+            generate_cave(GENERATED_WIDTH, GENERATED_HEIGHT, seed, registry.clone())
         }
+        _ => {
+            let Some(map) = map_handles.get(level).and_then(|handle| maps.get(handle)) else {
+                error!("map for {:?} has not been loaded", level);
+                return None;
+            };
+            map.clone()
+        }
+    };
+
+    // survival never ends, so terrain keeps going past the edges of its map
+    if level == Level::Survival {
+        map.terrain = Some(ChunkedTerrain::new(seed));
     }
 
-    map
+    map.add_stairs(floor, level.floor_count());
+    Some(map)
 }
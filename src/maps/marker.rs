@@ -0,0 +1,113 @@
+use bevy::prelude::*;
+
+/// Placeholder cells painted on a map's second REXPaint layer, the game decides what to place there.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MarkerKind {
+    PlayerStart,
+    EnemySpawn,
+    Portal,
+    ShopNpc,
+    LeaderboardNpc,
+    Campfire,
+    Item,
+    Loot,
+    Spikes,
+    HiddenTrap,
+    PressurePlate,
+    DartLauncher,
+    /// locks the door painted on the same cell of the tile layer
+    LockedDoor,
+    Key,
+    Brazier,
+    Rune,
+    Mirror,
+    /// a wall painted on the same cell of the tile layer opens up when its puzzle is solved
+    Passage,
+    /// loot handed out when its puzzle is solved
+    PuzzleReward,
+}
+
+impl MarkerKind {
+    pub const ALL: [MarkerKind; 19] = [
+        MarkerKind::PlayerStart,
+        MarkerKind::EnemySpawn,
+        MarkerKind::Portal,
+        MarkerKind::ShopNpc,
+        MarkerKind::LeaderboardNpc,
+        MarkerKind::Campfire,
+        MarkerKind::Item,
+        MarkerKind::Loot,
+        MarkerKind::Spikes,
+        MarkerKind::HiddenTrap,
+        MarkerKind::PressurePlate,
+        MarkerKind::DartLauncher,
+        MarkerKind::LockedDoor,
+        MarkerKind::Key,
+        MarkerKind::Brazier,
+        MarkerKind::Rune,
+        MarkerKind::Mirror,
+        MarkerKind::Passage,
+        MarkerKind::PuzzleReward,
+    ];
+
+    pub fn from_char(ch: char) -> Option<Self> {
+        match ch {
+            '@' => Some(MarkerKind::PlayerStart),
+            'd' => Some(MarkerKind::EnemySpawn),
+            'P' => Some(MarkerKind::Portal),
+            'S' => Some(MarkerKind::ShopNpc),
+            'L' => Some(MarkerKind::LeaderboardNpc),
+            '*' => Some(MarkerKind::Campfire),
+            'i' => Some(MarkerKind::Item),
+            '$' => Some(MarkerKind::Loot),
+            '^' => Some(MarkerKind::Spikes),
+            '!' => Some(MarkerKind::HiddenTrap),
+            '_' => Some(MarkerKind::PressurePlate),
+            '>' => Some(MarkerKind::DartLauncher),
+            '&' => Some(MarkerKind::LockedDoor),
+            'k' => Some(MarkerKind::Key),
+            'B' => Some(MarkerKind::Brazier),
+            'r' => Some(MarkerKind::Rune),
+            'm' => Some(MarkerKind::Mirror),
+            '=' => Some(MarkerKind::Passage),
+            'R' => Some(MarkerKind::PuzzleReward),
+            _ => None,
+        }
+    }
+
+    pub fn to_char(self) -> char {
+        match self {
+            MarkerKind::PlayerStart => '@',
+            MarkerKind::EnemySpawn => 'd',
+            MarkerKind::Portal => 'P',
+            MarkerKind::ShopNpc => 'S',
+            MarkerKind::LeaderboardNpc => 'L',
+            MarkerKind::Campfire => '*',
+            MarkerKind::Item => 'i',
+            MarkerKind::Loot => '$',
+            MarkerKind::Spikes => '^',
+            MarkerKind::HiddenTrap => '!',
+            MarkerKind::PressurePlate => '_',
+            MarkerKind::DartLauncher => '>',
+            MarkerKind::LockedDoor => '&',
+            MarkerKind::Key => 'k',
+            MarkerKind::Brazier => 'B',
+            MarkerKind::Rune => 'r',
+            MarkerKind::Mirror => 'm',
+            MarkerKind::Passage => '=',
+            MarkerKind::PuzzleReward => 'R',
+        }
+    }
+}
+
+#[derive(Component, Debug, Clone, Copy)]
+pub struct MapMarker {
+    pub kind: MarkerKind,
+    pub position: IVec2,
+}
+
+impl MapMarker {
+    pub fn new(kind: MarkerKind, position: IVec2) -> Self {
+        Self { kind, position }
+    }
+}
@@ -1,5 +1,21 @@
+pub mod cave;
+pub mod chunk;
+pub mod dungeon;
+pub mod flow_field;
+pub mod fov;
+pub mod loader;
 pub mod map;
+pub mod marker;
 pub mod tile;
+pub mod zone;
 
+pub use cave::*;
+pub use chunk::*;
+pub use dungeon::*;
+pub use flow_field::*;
+pub use fov::*;
+pub use loader::*;
 pub use map::*;
+pub use marker::*;
 pub use tile::*;
+pub use zone::*;
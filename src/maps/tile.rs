@@ -1,43 +1,204 @@
+use anyhow::{Context, bail};
 use bevy::prelude::*;
+use serde::Deserialize;
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::Path,
+    sync::Arc,
+};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum TileType {
-    Empty,
-    Wall,
-    Water,
-    Grass,
-    Stone,
-    Door,
+/// tiles the game itself places or reacts to, they always take the first ids in this order
+pub const BUILT_IN_TILES: [&str; 9] = [
+    "empty",
+    "wall",
+    "water",
+    "grass",
+    "stone",
+    "door",
+    "open_door",
+    "stairs_down",
+    "stairs_up",
+];
+
+/// Which way a stair or ladder tile takes the player.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+pub enum Stairs {
+    Up,
+    Down,
 }
 
-impl TileType {
-    pub fn is_walkable(&self) -> bool {
+impl Stairs {
+    /// how the floor number changes, floors are counted downwards from the first
+    pub fn floor_step(self) -> isize {
         match self {
-            TileType::Empty | TileType::Grass | TileType::Door => true,
-            TileType::Wall | TileType::Water | TileType::Stone => true,
+            Stairs::Up => -1,
+            Stairs::Down => 1,
         }
     }
 
-    pub fn to_char(&self) -> char {
+    pub fn opposite(self) -> Self {
         match self {
-            TileType::Empty => ' ',
-            TileType::Wall => '#',
-            TileType::Water => '~',
-            TileType::Grass => '.',
-            TileType::Stone => ':',
-            TileType::Door => '+',
+            Stairs::Up => Stairs::Down,
+            Stairs::Down => Stairs::Up,
         }
     }
+}
 
-    pub fn to_color(&self) -> Color {
-        match self {
-            TileType::Empty => Color::linear_rgb(0.0, 0.0, 0.0),
-            TileType::Wall => Color::linear_rgb(0.5, 0.5, 0.5),
-            TileType::Water => Color::linear_rgb(0.0, 0.0, 1.0),
-            TileType::Grass => Color::linear_rgb(0.0, 0.5, 0.0),
-            TileType::Stone => Color::linear_rgb(0.6, 0.6, 0.6),
-            TileType::Door => Color::linear_rgb(0.5, 0.25, 0.0),
+/// One kind of tile, as defined in `assets/tiles.ron`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct TileDef {
+    pub name: String,
+    /// also the glyph painted for it in REXPaint maps
+    pub glyph: char,
+    /// linear rgb
+    pub fg: [f32; 3],
+    #[serde(default)]
+    pub bg: [f32; 3],
+    /// whether the player, enemies and bosses can stand on it
+    pub walkable: bool,
+    #[serde(default)]
+    pub blocks_light: bool,
+    #[serde(default)]
+    pub blocks_projectiles: bool,
+    /// how much enemies would rather walk around it, open ground costs 1
+    #[serde(default = "default_movement_cost")]
+    pub movement_cost: u32,
+    /// played as the player steps onto it, relative to the assets folder
+    #[serde(default)]
+    pub footstep: Option<String>,
+    /// stepping onto it takes the player to another floor of the level
+    #[serde(default)]
+    pub stairs: Option<Stairs>,
+}
+
+fn default_movement_cost() -> u32 {
+    1
+}
+
+impl TileDef {
+    pub fn color(&self) -> Color {
+        let [r, g, b] = self.fg;
+        Color::linear_rgb(r, g, b)
+    }
+
+    pub fn bg_color(&self) -> Color {
+        let [r, g, b] = self.bg;
+        Color::linear_rgb(r, g, b)
+    }
+}
+
+/// Every tile kind the game knows, indexed by `TileType`. Read from `assets/tiles.ron` by
+/// the [`TileRegistryLoader`](crate::maps::TileRegistryLoader), kept as a resource once it's
+/// in, and carried by every map so its tiles can be looked up. Clones share their tiles.
+#[derive(Asset, Resource, TypePath, Debug, Clone)]
+pub struct TileRegistry {
+    defs: Arc<[TileDef]>,
+    by_glyph: Arc<HashMap<char, TileType>>,
+}
+
+impl TileRegistry {
+    /// relative to the assets folder
+    pub const PATH: &str = "tiles.ron";
+
+    /// reads the registry straight off disk, for tools and tests that run without an app
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let text = fs::read_to_string(path).context("can't read the file")?;
+        Self::from_ron(&text)
+    }
+
+    pub fn from_ron(text: &str) -> anyhow::Result<Self> {
+        let mut defs: Vec<TileDef> = ron::from_str(text)?;
+
+        // built-in tiles move to the front so their ids line up with `TileType`'s constants
+        let mut ordered = Vec::with_capacity(defs.len());
+        for name in BUILT_IN_TILES {
+            let Some(index) = defs.iter().position(|def| def.name == name) else {
+                bail!("the built-in tile '{}' is missing", name);
+            };
+            ordered.push(defs.remove(index));
+        }
+        ordered.append(&mut defs);
+
+        if ordered.len() > u16::MAX as usize {
+            bail!("too many tiles ({})", ordered.len());
+        }
+
+        let mut names = HashSet::new();
+        let mut by_glyph = HashMap::new();
+        for (index, def) in ordered.iter().enumerate() {
+            if !names.insert(def.name.clone()) {
+                bail!("the tile '{}' is defined twice", def.name);
+            }
+            if let Some(other) = by_glyph.insert(def.glyph, TileType(index as u16)) {
+                bail!(
+                    "the tiles '{}' and '{}' share the glyph '{}'",
+                    ordered[other.0 as usize].name,
+                    def.name,
+                    def.glyph
+                );
+            }
+            if def.movement_cost == 0 {
+                bail!("the tile '{}' has a movement cost of 0", def.name);
+            }
         }
+
+        Ok(Self {
+            defs: ordered.into(),
+            by_glyph: Arc::new(by_glyph),
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.defs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.defs.is_empty()
+    }
+
+    /// every registered tile, built-in ones first
+    pub fn all(&self) -> impl Iterator<Item = TileType> + use<> {
+        (0..self.defs.len() as u16).map(TileType)
+    }
+
+    pub fn from_char(&self, ch: char) -> Option<TileType> {
+        self.by_glyph.get(&ch).copied()
+    }
+
+    pub fn def(&self, tile_type: TileType) -> &TileDef {
+        &self.defs[tile_type.0 as usize]
+    }
+}
+
+/// the game's own `tiles.ron`, for tests that need real tiles
+#[cfg(test)]
+pub(crate) fn test_registry() -> TileRegistry {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("assets")
+        .join(TileRegistry::PATH);
+    TileRegistry::load(&path).expect("tiles.ron should load")
+}
+
+/// A kind of tile, indexing the `TileRegistry`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TileType(u16);
+
+impl TileType {
+    pub const EMPTY: TileType = TileType(0);
+    pub const WALL: TileType = TileType(1);
+    pub const WATER: TileType = TileType(2);
+    pub const GRASS: TileType = TileType(3);
+    pub const STONE: TileType = TileType(4);
+    /// closed doors block everything until opened
+    pub const DOOR: TileType = TileType(5);
+    pub const OPEN_DOOR: TileType = TileType(6);
+    pub const STAIRS_DOWN: TileType = TileType(7);
+    pub const STAIRS_UP: TileType = TileType(8);
+
+    /// open or closed, doors can always be walked through once opened
+    pub fn is_door(&self) -> bool {
+        matches!(*self, TileType::DOOR | TileType::OPEN_DOOR)
     }
 }
 
@@ -58,9 +219,84 @@ impl Tile {
     }
 
     pub fn empty() -> Self {
-        Self::new(TileType::Empty)
+        Self::new(TileType::EMPTY)
     }
     pub fn wall() -> Self {
-        Self::new(TileType::Wall)
+        Self::new(TileType::WALL)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BUILT_IN_GLYPHS: [char; 9] = [' ', '#', '~', '.', ',', '+', '/', '>', '<'];
+
+    fn tile(name: &str, glyph: char) -> String {
+        format!("(name: \"{name}\", glyph: '{glyph}', fg: (1.0, 1.0, 1.0), walkable: true)")
+    }
+
+    fn built_ins() -> Vec<String> {
+        BUILT_IN_TILES
+            .iter()
+            .zip(BUILT_IN_GLYPHS)
+            .map(|(name, glyph)| tile(name, glyph))
+            .collect()
+    }
+
+    fn parse(tiles: &[String]) -> anyhow::Result<TileRegistry> {
+        TileRegistry::from_ron(&format!("[{}]", tiles.join(", ")))
+    }
+
+    fn error(tiles: &[String]) -> String {
+        parse(tiles).expect_err("should be rejected").to_string()
+    }
+
+    #[test]
+    fn built_ins_keep_their_ids_out_of_order() {
+        let mut tiles = built_ins();
+        tiles.push(tile("lava", '%'));
+        // lava and stairs_up first
+        tiles.rotate_right(2);
+
+        let registry = parse(&tiles).expect("should load");
+        assert_eq!(registry.len(), 10);
+        assert_eq!(registry.def(TileType::STAIRS_UP).name, "stairs_up");
+        assert_eq!(registry.from_char('<'), Some(TileType::STAIRS_UP));
+        assert_eq!(registry.from_char('#'), Some(TileType::WALL));
+        assert_eq!(registry.from_char('%'), Some(TileType(9)));
+    }
+
+    #[test]
+    fn missing_built_in() {
+        let tiles: Vec<String> = built_ins()
+            .into_iter()
+            .filter(|tile| !tile.contains("\"door\""))
+            .collect();
+        assert!(error(&tiles).contains("'door' is missing"));
+    }
+
+    #[test]
+    fn duplicate_name() {
+        let mut tiles = built_ins();
+        tiles.extend([tile("lava", '%'), tile("lava", '&')]);
+        assert!(error(&tiles).contains("'lava' is defined twice"));
+    }
+
+    #[test]
+    fn shared_glyph() {
+        let mut tiles = built_ins();
+        tiles.push(tile("lava", '#'));
+        assert!(error(&tiles).contains("share the glyph '#'"));
+    }
+
+    #[test]
+    fn zero_movement_cost() {
+        let mut tiles = built_ins();
+        tiles.push(
+            "(name: \"mud\", glyph: 'm', fg: (1.0, 1.0, 1.0), walkable: true, movement_cost: 0)"
+                .to_string(),
+        );
+        assert!(error(&tiles).contains("'mud' has a movement cost of 0"));
     }
 }
@@ -0,0 +1,117 @@
+use bevy::prelude::*;
+use std::collections::HashSet;
+
+/// What spawns inside a spawn zone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SpawnCategory {
+    Horde,
+    Snake,
+    Giant,
+    Item,
+}
+
+/// Areas painted on a map's third REXPaint layer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ZoneKind {
+    /// the player can't be harmed here, e.g. the rest area
+    SafeZone,
+    /// only objects of one category spawn here
+    SpawnZone(SpawnCategory),
+    /// the braziers, runes, doors, passages and rewards inside belong to one light puzzle
+    Puzzle,
+}
+
+impl ZoneKind {
+    pub const ALL: [ZoneKind; 6] = [
+        ZoneKind::SafeZone,
+        ZoneKind::SpawnZone(SpawnCategory::Horde),
+        ZoneKind::SpawnZone(SpawnCategory::Snake),
+        ZoneKind::SpawnZone(SpawnCategory::Giant),
+        ZoneKind::SpawnZone(SpawnCategory::Item),
+        ZoneKind::Puzzle,
+    ];
+
+    pub fn from_char(ch: char) -> Option<Self> {
+        match ch {
+            'S' => Some(ZoneKind::SafeZone),
+            'd' => Some(ZoneKind::SpawnZone(SpawnCategory::Horde)),
+            's' => Some(ZoneKind::SpawnZone(SpawnCategory::Snake)),
+            'G' => Some(ZoneKind::SpawnZone(SpawnCategory::Giant)),
+            'i' => Some(ZoneKind::SpawnZone(SpawnCategory::Item)),
+            'p' => Some(ZoneKind::Puzzle),
+            _ => None,
+        }
+    }
+
+    pub fn to_char(self) -> char {
+        match self {
+            ZoneKind::SafeZone => 'S',
+            ZoneKind::SpawnZone(SpawnCategory::Horde) => 'd',
+            ZoneKind::SpawnZone(SpawnCategory::Snake) => 's',
+            ZoneKind::SpawnZone(SpawnCategory::Giant) => 'G',
+            ZoneKind::SpawnZone(SpawnCategory::Item) => 'i',
+            ZoneKind::Puzzle => 'p',
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum ZoneShape {
+    /// inclusive on both corners
+    Rect(IRect),
+    /// arbitrary painted cells
+    Mask(HashSet<IVec2>),
+}
+
+#[derive(Debug, Clone)]
+pub struct Zone {
+    pub kind: ZoneKind,
+    pub shape: ZoneShape,
+}
+
+impl Zone {
+    pub fn rect(kind: ZoneKind, min: IVec2, max: IVec2) -> Self {
+        Self {
+            kind,
+            shape: ZoneShape::Rect(IRect::from_corners(min, max)),
+        }
+    }
+
+    /// builds a zone from painted cells, collapsing them to a rectangle when they fill one
+    pub fn from_cells(kind: ZoneKind, cells: HashSet<IVec2>) -> Self {
+        let min = cells.iter().copied().reduce(IVec2::min).unwrap_or_default();
+        let max = cells.iter().copied().reduce(IVec2::max).unwrap_or_default();
+        let area = (max - min + IVec2::ONE).element_product() as usize;
+
+        if cells.len() == area {
+            Self::rect(kind, min, max)
+        } else {
+            Self {
+                kind,
+                shape: ZoneShape::Mask(cells),
+            }
+        }
+    }
+
+    /// every cell the zone covers
+    pub fn cells(&self) -> Vec<IVec2> {
+        match &self.shape {
+            ZoneShape::Rect(rect) => (rect.min.x..=rect.max.x)
+                .flat_map(|x| (rect.min.y..=rect.max.y).map(move |y| IVec2::new(x, y)))
+                .collect(),
+            ZoneShape::Mask(cells) => cells.iter().copied().collect(),
+        }
+    }
+
+    pub fn contains(&self, position: IVec2) -> bool {
+        match &self.shape {
+            ZoneShape::Rect(rect) => {
+                position.x >= rect.min.x
+                    && position.x <= rect.max.x
+                    && position.y >= rect.min.y
+                    && position.y <= rect.max.y
+            }
+            ZoneShape::Mask(cells) => cells.contains(&position),
+        }
+    }
+}
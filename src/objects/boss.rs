@@ -1,3 +1,4 @@
+use crate::objects::{GridPosition, RenderLayer, Renderable};
 use bevy::prelude::*;
 
 #[derive(Component)]
@@ -61,17 +62,90 @@ impl Boss {
         }
     }
 
-    pub fn take_damage(&mut self, damage: f32, segment_index: usize) -> bool {
-        let actual_damage = match &self.segments[segment_index].segment_type {
+    /// what a hit on a segment really deals, weak spots take more
+    pub fn damage_to(&self, damage: f32, segment_index: usize) -> f32 {
+        match &self.segments[segment_index].segment_type {
             SegmentType::Weakspot { damage_multiplier } => damage * damage_multiplier,
             _ => damage,
-        };
+        }
+    }
+
+    /// what the legend calls a segment, weak spots are worth pointing out
+    pub fn segment_name(&self, segment_index: usize) -> &'static str {
+        match (&self.segments[segment_index].segment_type, &self.boss_type) {
+            (SegmentType::Weakspot { .. }, _) => "Weak spot",
+            (SegmentType::Invulnerable, _) => "Armored segment",
+            (SegmentType::Regular, BossType::Snake) => "Snake",
+            (SegmentType::Regular, BossType::Giant) => "Giant",
+        }
+    }
 
-        self.health -= actual_damage;
+    pub fn take_damage(&mut self, damage: f32, segment_index: usize) -> bool {
+        self.health -= self.damage_to(damage, segment_index);
         self.health <= 0.0
     }
 }
 
+/// draws one segment of the boss it's a child of
+#[derive(Component)]
+#[require(Renderable = Renderable::on(RenderLayer::Enemies))]
+pub struct BossSegmentSprite {
+    pub index: usize,
+}
+
+type SegmentSprites<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static BossSegmentSprite,
+        &'static ChildOf,
+        &'static mut Renderable,
+        &'static mut GridPosition,
+        &'static mut Visibility,
+    ),
+>;
+
+/// gives each new boss a renderable child per segment, and keeps them on their segments
+pub fn sync_boss_segments(
+    mut commands: Commands,
+    new_bosses: Query<(Entity, &Boss), Added<Boss>>,
+    boss_query: Query<&Boss>,
+    mut sprite_query: SegmentSprites,
+) {
+    for (entity, boss) in new_bosses.iter() {
+        commands.entity(entity).with_children(|parent| {
+            for (index, segment) in boss.segments.iter().enumerate() {
+                parent.spawn((
+                    BossSegmentSprite { index },
+                    Renderable::new(segment.character, segment.color, RenderLayer::Enemies)
+                        .named(boss.segment_name(index)),
+                    GridPosition(segment.position),
+                ));
+            }
+        });
+    }
+
+    for (sprite, child_of, mut renderable, mut position, mut visibility) in sprite_query.iter_mut()
+    {
+        // bosses on floors the player has left are disabled, and can't be found
+        let Some(segment) = boss_query
+            .get(child_of.parent())
+            .ok()
+            .and_then(|boss| boss.segments.get(sprite.index))
+        else {
+            visibility.set_if_neq(Visibility::Hidden);
+            continue;
+        };
+        position.set_if_neq(GridPosition(segment.position));
+        renderable.set_if_neq(Renderable {
+            glyph: segment.character,
+            fg: segment.color,
+            ..*renderable
+        });
+        visibility.set_if_neq(Visibility::Inherited);
+    }
+}
+
 fn create_snake_segments(start_pos: IVec2) -> Vec<BossSegment> {
     let mut segments = Vec::new();
 
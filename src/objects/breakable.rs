@@ -0,0 +1,157 @@
+use crate::{
+    maps::Map,
+    objects::{Orb, RenderLayer, Renderable, Visual},
+};
+use bevy::prelude::*;
+use rand::prelude::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreakableKind {
+    Barrel,
+    Crate,
+    Pot,
+}
+
+impl BreakableKind {
+    pub const ALL: [BreakableKind; 3] = [
+        BreakableKind::Barrel,
+        BreakableKind::Crate,
+        BreakableKind::Pot,
+    ];
+
+    pub fn glyph(&self) -> char {
+        match self {
+            BreakableKind::Barrel => '0',
+            BreakableKind::Crate => '=',
+            BreakableKind::Pot => 'u',
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            BreakableKind::Barrel => "Barrel",
+            BreakableKind::Crate => "Crate",
+            BreakableKind::Pot => "Pot",
+        }
+    }
+
+    pub fn color(&self) -> Color {
+        match self {
+            BreakableKind::Barrel => Color::linear_rgb(0.6, 0.35, 0.1),
+            BreakableKind::Crate => Color::linear_rgb(0.75, 0.55, 0.25),
+            BreakableKind::Pot => Color::linear_rgb(0.8, 0.4, 0.3),
+        }
+    }
+
+    pub fn health(&self) -> f32 {
+        match self {
+            BreakableKind::Barrel => 50.0,
+            BreakableKind::Crate => 75.0,
+            BreakableKind::Pot => 25.0,
+        }
+    }
+
+    /// weighted drops, sturdier objects tend to hold more
+    pub fn drop_table(&self) -> &'static [(LootDrop, u32)] {
+        match self {
+            BreakableKind::Barrel => &[
+                (LootDrop::Nothing, 2),
+                (LootDrop::Orb { experience: 10 }, 5),
+                (
+                    LootDrop::Orbs {
+                        count: 3,
+                        experience: 10,
+                    },
+                    2,
+                ),
+            ],
+            BreakableKind::Crate => &[
+                (LootDrop::Nothing, 1),
+                (LootDrop::Orb { experience: 25 }, 4),
+                (
+                    LootDrop::Orbs {
+                        count: 4,
+                        experience: 15,
+                    },
+                    3,
+                ),
+            ],
+            BreakableKind::Pot => &[
+                (LootDrop::Nothing, 4),
+                (LootDrop::Orb { experience: 5 }, 4),
+                (LootDrop::Orb { experience: 50 }, 1),
+            ],
+        }
+    }
+}
+
+/// what falls out of a broken object
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LootDrop {
+    Nothing,
+    Orb { experience: u32 },
+    Orbs { count: u32, experience: u32 },
+}
+
+impl LootDrop {
+    /// the first orb lands where the object stood, the rest scatter onto open cells around it
+    pub fn spawn(&self, commands: &mut Commands, position: IVec2, map: Option<&Map>) {
+        let (count, experience) = match *self {
+            LootDrop::Nothing => return,
+            LootDrop::Orb { experience } => (1, experience),
+            LootDrop::Orbs { count, experience } => (count, experience),
+        };
+
+        let mut rng = rand::rng();
+        let mut spots: Vec<IVec2> = (-1..=1)
+            .flat_map(|x| (-1..=1).map(move |y| position + IVec2::new(x, y)))
+            .filter(|&spot| spot != position)
+            .filter(|&spot| map.is_none_or(|map| map.is_walkable(spot.x, spot.y)))
+            .collect();
+        spots.shuffle(&mut rng);
+
+        let spots = std::iter::once(position).chain(spots.into_iter().cycle());
+        for spot in spots.take(count as usize) {
+            commands.spawn(Orb::new(spot, experience));
+        }
+    }
+}
+
+/// barrels, crates and pots that projectiles can smash open for loot
+#[derive(Component)]
+#[require(Renderable = Renderable::on(RenderLayer::Map))]
+pub struct Breakable {
+    pub kind: BreakableKind,
+    pub position: IVec2,
+    pub health: f32,
+}
+
+impl Breakable {
+    pub fn new(kind: BreakableKind, position: IVec2) -> Self {
+        Self {
+            kind,
+            position,
+            health: kind.health(),
+        }
+    }
+
+    pub fn roll_drop(&self, rng: &mut impl Rng) -> LootDrop {
+        self.kind
+            .drop_table()
+            .choose_weighted(rng, |(_, weight)| *weight)
+            .map(|(drop, _)| *drop)
+            .unwrap_or(LootDrop::Nothing)
+    }
+}
+
+impl Visual for Breakable {
+    fn cell(&self) -> IVec2 {
+        self.position
+    }
+
+    fn restyle(&self, renderable: &mut Renderable) {
+        renderable.glyph = self.kind.glyph();
+        renderable.fg = self.kind.color();
+        renderable.name = Some(self.kind.name());
+    }
+}
@@ -1,10 +1,11 @@
+use crate::objects::{RenderLayer, Renderable, Visual};
 use bevy::prelude::*;
 
 #[derive(Component)]
+#[require(Renderable = Renderable::on(RenderLayer::Map).named("Campfire"))]
 pub struct Campfire {
     pub position: IVec2,
     pub animation_timer: Timer,
-    pub ember_spawn_timer: Timer,
     pub current_frame: usize,
     pub characters: Vec<char>,
     pub colors: Vec<Color>,
@@ -23,7 +24,6 @@ impl Campfire {
         Self {
             position,
             animation_timer: Timer::from_seconds(0.2, TimerMode::Repeating),
-            ember_spawn_timer: Timer::from_seconds(10.0, TimerMode::Repeating),
             current_frame: 0,
             characters,
             colors,
@@ -44,3 +44,13 @@ impl Campfire {
         }
     }
 }
+
+impl Visual for Campfire {
+    fn cell(&self) -> IVec2 {
+        self.position
+    }
+
+    fn restyle(&self, renderable: &mut Renderable) {
+        (renderable.glyph, renderable.fg) = self.get_current_visual();
+    }
+}
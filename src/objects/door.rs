@@ -0,0 +1,79 @@
+use crate::{
+    objects::{Player, RenderLayer, Renderable, Visual},
+    systems::Despawn,
+};
+use bevy::prelude::*;
+
+/// A door on the map. Whether it's open lives on the map tile, so sight, movement and
+/// pathing all follow it, this only tracks the lock.
+#[derive(Component)]
+#[require(Renderable = Renderable::new('+', Color::linear_rgb(0.8, 0.6, 0.2), RenderLayer::Map).named("Locked door"))]
+pub struct Door {
+    pub position: IVec2,
+    pub locked: bool,
+}
+
+impl Door {
+    pub fn new(position: IVec2, locked: bool) -> Self {
+        Self { position, locked }
+    }
+}
+
+impl Visual for Door {
+    fn cell(&self) -> IVec2 {
+        self.position
+    }
+
+    /// only locked doors are picked out in brass, the rest are left to the map's own tiles
+    fn hidden(&self) -> bool {
+        !self.locked
+    }
+}
+
+/// picked up by walking over it, opens any one locked door on the same level
+#[derive(Component)]
+#[require(Renderable = Renderable::on(RenderLayer::Items).named("Key"))]
+pub struct DoorKey {
+    pub position: IVec2,
+}
+
+impl DoorKey {
+    pub fn new(position: IVec2) -> Self {
+        Self { position }
+    }
+
+    pub fn glyph(&self) -> char {
+        'k'
+    }
+
+    pub fn color(&self) -> Color {
+        Color::linear_rgb(1.0, 0.85, 0.2)
+    }
+}
+
+impl Visual for DoorKey {
+    fn cell(&self) -> IVec2 {
+        self.position
+    }
+
+    fn restyle(&self, renderable: &mut Renderable) {
+        renderable.glyph = self.glyph();
+        renderable.fg = self.color();
+    }
+}
+
+/// walking onto a key puts it in the player's pocket
+pub fn process_key_pickup(
+    mut commands: Commands,
+    mut player_query: Query<&mut Player>,
+    key_query: Query<(Entity, &DoorKey), Without<Despawn>>,
+) {
+    if let Ok(mut player) = player_query.single_mut() {
+        for (entity, key) in key_query.iter() {
+            if key.position == player.world_position {
+                player.keys += 1;
+                commands.entity(entity).insert(Despawn);
+            }
+        }
+    }
+}
@@ -1,6 +1,8 @@
+use crate::objects::{RenderLayer, Renderable, Visual};
 use bevy::prelude::*;
 
 #[derive(Component)]
+#[require(Renderable = Renderable::new('d', Color::WHITE, RenderLayer::Enemies).named("Enemy"))]
 pub struct Enemy {
     pub health: f32,
     pub position: IVec2,
@@ -18,3 +20,9 @@ impl Enemy {
         }
     }
 }
+
+impl Visual for Enemy {
+    fn cell(&self) -> IVec2 {
+        self.position
+    }
+}
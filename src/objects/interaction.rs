@@ -7,8 +7,11 @@ pub struct Interaction {
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum InteractionType {
+    Brazier,
     Campfire,
+    Door,
     LeaderboardNpc,
+    Mirror,
     ShopNpc,
 }
 
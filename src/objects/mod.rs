@@ -1,6 +1,7 @@
 pub mod boss;
+pub mod breakable;
 pub mod campfire;
-pub mod ember;
+pub mod door;
 pub mod enemy;
 pub mod interaction;
 pub mod light;
@@ -9,11 +10,15 @@ pub mod orb;
 pub mod player;
 pub mod portal;
 pub mod projectile;
+pub mod puzzle;
+pub mod renderable;
 pub mod shop_npc;
+pub mod trap;
 
 pub use boss::*;
+pub use breakable::*;
 pub use campfire::*;
-pub use ember::*;
+pub use door::*;
 pub use enemy::*;
 pub use interaction::*;
 pub use light::*;
@@ -22,4 +27,7 @@ pub use orb::*;
 pub use player::*;
 pub use portal::*;
 pub use projectile::*;
+pub use puzzle::*;
+pub use renderable::*;
 pub use shop_npc::*;
+pub use trap::*;
@@ -1,7 +1,8 @@
-use crate::{objects::*, resources::*, systems::Despawn};
+use crate::{maps::*, objects::*, resources::*, systems::Despawn};
 use bevy::prelude::*;
 
 #[derive(Component)]
+#[require(Renderable = Renderable::new('o', Color::linear_rgb(0.8, 0.2, 0.8), RenderLayer::Items).named("Experience orb"))]
 pub struct Orb {
     pub position: IVec2,
     pub precise_position: Vec2,
@@ -18,12 +19,19 @@ impl Orb {
     }
 }
 
+impl Visual for Orb {
+    fn cell(&self) -> IVec2 {
+        self.position
+    }
+}
+
 /// orbs within a certain radius will move towards the player with increasing speed.
 pub fn orb_movement(
     mut orb_query: Query<&mut Orb>,
     player_query: Query<&Player>,
     time: Res<Time>,
     _camera_offset: Res<CameraOffset>,
+    map: Option<Res<Map>>,
 ) {
     if let Ok(player) = player_query.single() {
         let player_world_pos = player.world_position;
@@ -41,8 +49,27 @@ pub fn orb_movement(
                 let speed_factor = 1.0 - (distance / ATTRACTION_RADIUS);
                 let speed = MIN_SPEED + (max_speed - MIN_SPEED) * speed_factor * speed_factor;
                 let movement = direction_to_player.normalize() * speed * time.delta_secs();
-                orb.precise_position += movement;
-                orb.position = orb.precise_position.as_ivec2();
+
+                // orbs slide along walls rather than drifting through them
+                let current = orb.precise_position;
+                let walkable = |position: Vec2| {
+                    let cell = position.as_ivec2();
+                    map.as_ref()
+                        .is_none_or(|map| map.is_walkable(cell.x, cell.y))
+                };
+                let next = [
+                    movement,
+                    Vec2::new(movement.x, 0.0),
+                    Vec2::new(0.0, movement.y),
+                ]
+                .into_iter()
+                .map(|movement| current + movement)
+                .find(|&position| walkable(position));
+
+                if let Some(next) = next {
+                    orb.precise_position = next;
+                    orb.position = orb.precise_position.as_ivec2();
+                }
             }
         }
     }
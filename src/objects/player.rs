@@ -1,4 +1,7 @@
-use crate::spells::Arcanum;
+use crate::{
+    objects::{RenderLayer, Renderable, Visual},
+    spells::Arcanum,
+};
 use bevy::prelude::*;
 
 pub fn experience_for_level(level: u32) -> u32 {
@@ -6,6 +9,7 @@ pub fn experience_for_level(level: u32) -> u32 {
 }
 
 #[derive(Component, Clone)]
+#[require(Renderable = Renderable::new('@', Color::WHITE, RenderLayer::Player).named("You"))]
 pub struct Player {
     pub health: f32,
     pub max_health: f32,
@@ -16,6 +20,8 @@ pub struct Player {
     pub level: u32,
     pub experience_to_next_level: u32,
     pub arcanum: Arcanum,
+    /// keys picked up on the current level
+    pub keys: u32,
 }
 
 impl Player {
@@ -30,6 +36,23 @@ impl Player {
             level: 1,
             experience_to_next_level: experience_for_level(1),
             arcanum: Arcanum::new(),
+            keys: 0,
         }
     }
+
+    /// moves the player to a world position, keeping its screen position in sync with the camera
+    pub fn place(&mut self, world_position: IVec2, camera_offset: IVec2, terminal_size: UVec2) {
+        let screen_position = world_position + camera_offset;
+        self.world_position = world_position;
+        self.position = IVec2::new(
+            screen_position.x,
+            terminal_size.y as i32 - 1 - screen_position.y,
+        );
+    }
+}
+
+impl Visual for Player {
+    fn cell(&self) -> IVec2 {
+        self.world_position
+    }
 }
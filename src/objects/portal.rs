@@ -1,6 +1,8 @@
+use crate::objects::{RenderLayer, Renderable, Visual};
 use bevy::prelude::*;
 
 #[derive(Component)]
+#[require(Renderable = Renderable::new('P', Color::linear_rgb(0.0, 1.0, 1.0), RenderLayer::Map).named("Portal"))]
 pub struct Portal {
     pub position: IVec2,
 }
@@ -10,3 +12,9 @@ impl Portal {
         Self { position }
     }
 }
+
+impl Visual for Portal {
+    fn cell(&self) -> IVec2 {
+        self.position
+    }
+}
@@ -1,8 +1,14 @@
 use crate::CameraOffset;
-use crate::objects::boss::Boss;
+use crate::effects::damage_numbers::*;
+use crate::effects::particles::ParticleEmitter;
+use crate::maps::map::Map;
+use crate::objects::boss::{Boss, SegmentType};
+use crate::objects::breakable::Breakable;
 use crate::objects::enemy::Enemy;
 use crate::objects::orb::Orb;
 use crate::objects::player::Player;
+use crate::objects::renderable::*;
+use crate::resources::camera::CameraEffects;
 use crate::resources::channels::*;
 use crate::resources::kill_count::KillCount;
 use crate::resources::scene_lock::SceneLock;
@@ -11,8 +17,15 @@ use crate::systems::cleanup::Despawn;
 use bevy::prelude::*;
 use bevy_ascii_terminal::*;
 use bevy_kira_audio::prelude::*;
+use rand::prelude::*;
+
+/// chance that a projectile hit is a critical one
+const CRITICAL_CHANCE: f64 = 0.1;
+
+const CRITICAL_MULTIPLIER: f32 = 2.0;
 
 #[derive(Component)]
+#[require(Renderable = Renderable::new('*', Color::linear_rgb(1.0, 0.7, 0.0), RenderLayer::Projectiles).named("Magic bolt"))]
 pub struct Projectile {
     pub position: IVec2,
     pub target: Option<Entity>,
@@ -37,9 +50,27 @@ impl Projectile {
     }
 }
 
+impl Visual for Projectile {
+    fn cell(&self) -> IVec2 {
+        self.position
+    }
+}
+
 #[derive(Component)]
 pub struct Fireball;
 
+impl Fireball {
+    /// fireballs are projectiles too, this replaces their usual look
+    pub fn renderable() -> Renderable {
+        Renderable::new(
+            '@',
+            Color::linear_rgb(1.0, 0.3, 0.0),
+            RenderLayer::Projectiles,
+        )
+        .named("Fireball")
+    }
+}
+
 pub fn auto_cast(
     mut commands: Commands,
     player_query: Query<&Player>,
@@ -115,6 +146,7 @@ pub fn process_projectiles(
     camera_offset: Res<CameraOffset>,
     time: Res<Time>,
     _scene_lock: Res<SceneLock>,
+    map: Option<Res<Map>>,
 ) {
     if let Ok(terminal) = terminal_query.single() {
         let terminal_size = terminal.size();
@@ -126,6 +158,7 @@ pub fn process_projectiles(
                 continue;
             }
 
+            let previous_position = projectile.position;
             let speed = projectile.speed * time.delta_secs();
             let mut target_exists = false;
 
@@ -175,6 +208,15 @@ pub fn process_projectiles(
                 continue;
             }
 
+            // projectiles stop at the first wall in their way
+            if let Some(map) = &map
+                && !map.projectile_path_clear(previous_position, projectile.position)
+            {
+                projectile.position = previous_position;
+                commands.entity(entity).insert(Despawn);
+                continue;
+            }
+
             let draw_position = projectile.position + camera_offset.0;
             if draw_position.x < 0
                 || draw_position.x >= terminal_size[0] as i32
@@ -197,6 +239,7 @@ pub fn process_fireballs(
     camera_offset: Res<CameraOffset>,
     time: Res<Time>,
     _scene_lock: Res<SceneLock>,
+    map: Option<Res<Map>>,
 ) {
     if let Ok(terminal) = terminal_query.single() {
         let terminal_size = terminal.size();
@@ -209,6 +252,7 @@ pub fn process_fireballs(
                 continue;
             }
 
+            let previous_position = fireball.position;
             let speed = fireball.speed * time.delta_secs();
             let mut target_exists = false;
             let mut target_position = None;
@@ -232,7 +276,13 @@ pub fn process_fireballs(
                 let distance = direction.length();
 
                 if distance <= speed {
-                    fireball.position = target_pos;
+                    let path_clear = map
+                        .as_ref()
+                        .is_none_or(|map| map.projectile_path_clear(fireball.position, target_pos));
+                    if path_clear {
+                        fireball.position = target_pos;
+                    }
+                    ParticleEmitter::explosion(fireball.position).burst(&mut commands, 16);
                     commands.entity(entity).insert(Despawn);
                     continue;
                 } else {
@@ -248,6 +298,14 @@ pub fn process_fireballs(
                 continue;
             }
 
+            if let Some(map) = &map
+                && !map.projectile_path_clear(previous_position, fireball.position)
+            {
+                fireball.position = previous_position;
+                commands.entity(entity).insert(Despawn);
+                continue;
+            }
+
             let draw_position = fireball.position + camera_offset.0;
             if draw_position.x < -10
                 || draw_position.x > terminal_size[0] as i32 + 10
@@ -266,21 +324,29 @@ pub fn process_collisions(
     projectile_query: Query<(Entity, &Projectile)>,
     mut enemy_query: Query<(Entity, &mut Enemy), Without<Despawn>>,
     mut boss_query: Query<(Entity, &mut Boss), Without<Despawn>>,
+    mut breakable_query: Query<(Entity, &mut Breakable), Without<Despawn>>,
     mut kill_count: ResMut<KillCount>,
+    mut camera_effects: ResMut<CameraEffects>,
     _scene_lock: Res<SceneLock>,
+    map: Option<Res<Map>>,
 ) {
-    // todo: currently only checking projectiles against enemies
+    let mut rng = rand::rng();
     for (projectile_entity, projectile) in projectile_query.iter() {
         for (enemy_entity, mut enemy) in enemy_query.iter_mut() {
             if projectile.position == enemy.position {
                 if enemy.health > 0.0 {
                     // take damage
-                    enemy.health -= projectile.damage;
+                    let (damage, kind) = roll_damage(&mut rng, projectile.damage);
+                    enemy.health -= damage;
+                    DamageNumber::spawn(&mut commands, enemy.position, damage, kind);
+                    commands.entity(enemy_entity).insert(HitFlash::default());
 
                     // if enemy's health pool is depleted, mark it for despawn
                     if enemy.health <= 0.0 {
                         // spawn an orb at the enemy's position before despawning
                         commands.spawn(Orb::new(enemy.position, 10));
+                        ParticleEmitter::shards(enemy.position, Color::linear_rgb(0.9, 0.9, 0.8))
+                            .burst(&mut commands, 6);
                         commands.entity(enemy_entity).insert(Despawn);
                         kill_count.enemies += 1;
                     }
@@ -294,13 +360,27 @@ pub fn process_collisions(
         for (boss_entity, mut boss) in boss_query.iter_mut() {
             for (segment_index, segment) in boss.segments.iter().enumerate() {
                 if projectile.position == segment.position {
-                    let is_defeated = boss.take_damage(projectile.damage, segment_index);
+                    let (damage, kind) = roll_damage(&mut rng, projectile.damage);
+                    let kind = match kind {
+                        DamageKind::Normal
+                            if matches!(segment.segment_type, SegmentType::Weakspot { .. }) =>
+                        {
+                            DamageKind::Weakspot
+                        }
+                        kind => kind,
+                    };
+                    let dealt = boss.damage_to(damage, segment_index);
+                    DamageNumber::spawn(&mut commands, segment.position, dealt, kind);
+                    commands.entity(boss_entity).insert(HitFlash::default());
+
+                    let is_defeated = boss.take_damage(damage, segment_index);
                     if is_defeated {
                         for segment in &boss.segments {
                             commands.spawn(Orb::new(segment.position, 50)); // bosses are worth more experience than normal enemies
                         }
                         commands.entity(boss_entity).insert(Despawn);
                         kill_count.enemies += 1;
+                        camera_effects.boss_defeated();
                     }
 
                     commands.entity(projectile_entity).insert(Despawn);
@@ -308,5 +388,36 @@ pub fn process_collisions(
                 }
             }
         }
+
+        for (breakable_entity, mut breakable) in breakable_query.iter_mut() {
+            if projectile.position == breakable.position {
+                let (damage, kind) = roll_damage(&mut rng, projectile.damage);
+                breakable.health -= damage;
+                DamageNumber::spawn(&mut commands, breakable.position, damage, kind);
+                commands
+                    .entity(breakable_entity)
+                    .insert(HitFlash::default());
+
+                // smashed open, whatever was inside spills out
+                if breakable.health <= 0.0 {
+                    let drop = breakable.roll_drop(&mut rng);
+                    drop.spawn(&mut commands, breakable.position, map.as_deref());
+                    ParticleEmitter::shards(breakable.position, breakable.kind.color())
+                        .burst(&mut commands, 8);
+                    commands.entity(breakable_entity).insert(Despawn);
+                }
+
+                commands.entity(projectile_entity).insert(Despawn);
+            }
+        }
+    }
+}
+
+/// rolls whether a landed hit is a critical one, returning the damage it deals
+fn roll_damage(rng: &mut impl Rng, damage: f32) -> (f32, DamageKind) {
+    if rng.random_bool(CRITICAL_CHANCE) {
+        (damage * CRITICAL_MULTIPLIER, DamageKind::Critical)
+    } else {
+        (damage, DamageKind::Normal)
     }
 }
@@ -0,0 +1,179 @@
+use crate::{
+    maps::Zone,
+    objects::{LightEmitter, RenderLayer, Renderable, Visual},
+};
+use bevy::prelude::*;
+
+/// how much firelight a puzzle piece needs on its cell to count as lit. runes and mirrors
+/// only answer to warm light, the player's own cold glow has next to no red in it
+pub const FIRELIGHT_THRESHOLD: f32 = 0.3;
+
+/// the part of a cell's light that comes from fire
+pub fn firelight(light: LinearRgba) -> f32 {
+    light.red
+}
+
+/// lit by hand, or catches from enough firelight nearby, then burns for the rest of the level
+#[derive(Component)]
+#[require(Renderable = Renderable::on(RenderLayer::Map).named("Brazier"))]
+pub struct Brazier {
+    pub position: IVec2,
+    pub lit: bool,
+}
+
+impl Brazier {
+    pub fn new(position: IVec2) -> Self {
+        Self {
+            position,
+            lit: false,
+        }
+    }
+
+    pub fn glyph(&self) -> char {
+        'Y'
+    }
+
+    pub fn color(&self) -> Color {
+        if self.lit {
+            Color::linear_rgb(1.0, 0.55, 0.1)
+        } else {
+            Color::linear_rgb(0.4, 0.35, 0.3)
+        }
+    }
+
+    pub fn light() -> LightEmitter {
+        LightEmitter::new(Color::linear_rgba(1.0, 0.6, 0.2, 0.4), 8.0, 0.85, 1.65)
+    }
+}
+
+impl Visual for Brazier {
+    fn cell(&self) -> IVec2 {
+        self.position
+    }
+
+    fn restyle(&self, renderable: &mut Renderable) {
+        renderable.glyph = self.glyph();
+        renderable.fg = self.color();
+    }
+}
+
+/// glows for as long as firelight falls on it
+#[derive(Component)]
+#[require(Renderable = Renderable::on(RenderLayer::Map).named("Rune"))]
+pub struct Rune {
+    pub position: IVec2,
+    pub lit: bool,
+}
+
+impl Rune {
+    pub fn new(position: IVec2) -> Self {
+        Self {
+            position,
+            lit: false,
+        }
+    }
+
+    pub fn glyph(&self) -> char {
+        '%'
+    }
+
+    pub fn color(&self) -> Color {
+        if self.lit {
+            Color::linear_rgb(1.0, 0.9, 0.4)
+        } else {
+            Color::linear_rgb(0.25, 0.25, 0.35)
+        }
+    }
+}
+
+impl Visual for Rune {
+    fn cell(&self) -> IVec2 {
+        self.position
+    }
+
+    fn restyle(&self, renderable: &mut Renderable) {
+        renderable.glyph = self.glyph();
+        renderable.fg = self.color();
+    }
+}
+
+/// throws the firelight falling on it along the way it faces, turned by hand
+#[derive(Component)]
+#[require(Renderable = Renderable::on(RenderLayer::Map).named("Mirror"))]
+pub struct Mirror {
+    pub position: IVec2,
+    pub facing: IVec2,
+    pub lit: bool,
+    /// where the reflected light lands
+    pub target: IVec2,
+}
+
+impl Mirror {
+    /// how far reflected light carries
+    pub const REACH: i32 = 8;
+    pub const LIGHT_INTENSITY: f32 = 0.9;
+
+    pub fn new(position: IVec2) -> Self {
+        Self {
+            position,
+            facing: IVec2::X,
+            lit: false,
+            target: position,
+        }
+    }
+
+    /// turns a quarter clockwise
+    pub fn turn(&mut self) {
+        self.facing = IVec2::new(self.facing.y, -self.facing.x);
+    }
+
+    pub fn glyph(&self) -> char {
+        match (self.facing.x, self.facing.y) {
+            (1, _) => '>',
+            (-1, _) => '<',
+            (_, 1) => '^',
+            _ => 'v',
+        }
+    }
+
+    pub fn color(&self) -> Color {
+        if self.lit {
+            Color::linear_rgb(1.0, 1.0, 0.8)
+        } else {
+            Color::linear_rgb(0.6, 0.7, 0.8)
+        }
+    }
+
+    /// starts dark, turned up to `LIGHT_INTENSITY` while the mirror is lit
+    pub fn light() -> LightEmitter {
+        LightEmitter::new(Color::linear_rgba(1.0, 0.75, 0.35, 0.4), 3.0, 0.0, 1.5)
+    }
+}
+
+impl Visual for Mirror {
+    fn cell(&self) -> IVec2 {
+        self.position
+    }
+
+    fn restyle(&self, renderable: &mut Renderable) {
+        renderable.glyph = self.glyph();
+        renderable.fg = self.color();
+    }
+}
+
+/// A puzzle zone painted on the map. Once every brazier and rune inside is lit at the same
+/// time it opens its doors, reveals its passages and hands out its rewards, once.
+#[derive(Component)]
+pub struct LightPuzzle {
+    pub zone: Zone,
+    pub solved: bool,
+}
+
+impl LightPuzzle {
+    pub fn new(zone: Zone) -> Self {
+        Self {
+            zone,
+            solved: false,
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use bevy::prelude::*;
+
+/// draw order, each layer is drawn over the ones before it
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum RenderLayer {
+    /// fixtures that belong to the map, like doors, traps and fires
+    Map,
+    #[default]
+    Items,
+    Enemies,
+    Projectiles,
+    Player,
+}
+
+/// A glyph drawn on the world cell in the entity's [`GridPosition`]. The render pass draws
+/// every renderable in view, sorted by layer.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+#[require(GridPosition, Visibility)]
+pub struct Renderable {
+    pub glyph: char,
+    pub fg: Color,
+    /// the map's own background shows through when unset
+    pub bg: Option<Color>,
+    pub layer: RenderLayer,
+    /// what the legend calls it, effects like particles are left out
+    pub name: Option<&'static str>,
+}
+
+impl Renderable {
+    pub fn new(glyph: char, fg: Color, layer: RenderLayer) -> Self {
+        Self {
+            glyph,
+            fg,
+            bg: None,
+            layer,
+            name: None,
+        }
+    }
+
+    pub fn named(self, name: &'static str) -> Self {
+        Self {
+            name: Some(name),
+            ..self
+        }
+    }
+
+    /// a blank glyph on a layer, for objects that restyle themselves before they're drawn
+    pub fn on(layer: RenderLayer) -> Self {
+        Self::new(' ', Color::WHITE, layer)
+    }
+}
+
+impl Default for Renderable {
+    fn default() -> Self {
+        Self::on(RenderLayer::default())
+    }
+}
+
+/// the world cell a renderable is drawn on
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq, Eq, Deref, DerefMut)]
+pub struct GridPosition(pub IVec2);
+
+/// Objects that keep their own position and are drawn through a [`Renderable`].
+/// [`sync_renderables`] copies their position over and lets them restyle every frame.
+pub trait Visual: Component {
+    /// the world cell the object is drawn on
+    fn cell(&self) -> IVec2;
+
+    /// objects that always look the same keep the renderable they were spawned with
+    fn restyle(&self, _renderable: &mut Renderable) {}
+
+    fn hidden(&self) -> bool {
+        false
+    }
+}
+
+pub fn sync_renderables<T: Visual>(
+    mut query: Query<(&T, &mut Renderable, &mut GridPosition, &mut Visibility)>,
+) {
+    for (visual, mut renderable, mut position, mut visibility) in query.iter_mut() {
+        position.set_if_neq(GridPosition(visual.cell()));
+
+        let mut look = *renderable;
+        visual.restyle(&mut look);
+        renderable.set_if_neq(look);
+
+        visibility.set_if_neq(if visual.hidden() {
+            Visibility::Hidden
+        } else {
+            Visibility::Inherited
+        });
+    }
+}
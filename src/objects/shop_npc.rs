@@ -1,6 +1,8 @@
+use crate::objects::{RenderLayer, Renderable, Visual};
 use bevy::prelude::*;
 
 #[derive(Component)]
+#[require(Renderable = Renderable::new('S', Color::linear_rgb(0.0, 1.0, 1.0), RenderLayer::Enemies).named("Shopkeeper"))]
 pub struct ShopNpc {
     pub position: IVec2,
 }
@@ -10,3 +12,9 @@ impl ShopNpc {
         Self { position }
     }
 }
+
+impl Visual for ShopNpc {
+    fn cell(&self) -> IVec2 {
+        self.position
+    }
+}
@@ -0,0 +1,129 @@
+use crate::objects::{RenderLayer, Renderable, Visual};
+use bevy::prelude::*;
+
+/// how often spikes hurt whoever is standing on them
+const SPIKE_INTERVAL: f32 = 1.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrapKind {
+    /// hurts anyone standing on it, again every `SPIKE_INTERVAL` seconds
+    Spikes,
+    /// fires a dart from its launcher the moment something steps on it
+    PressurePlate { launcher: IVec2 },
+}
+
+#[derive(Component)]
+#[require(Renderable = Renderable::on(RenderLayer::Map))]
+pub struct Trap {
+    pub kind: TrapKind,
+    pub position: IVec2,
+    pub damage: f32,
+    /// hidden traps still go off, they just aren't drawn until spotted or sprung
+    pub hidden: bool,
+    pub cooldown: Timer,
+    pub pressed: bool,
+}
+
+impl Trap {
+    pub fn spikes(position: IVec2, hidden: bool) -> Self {
+        let mut cooldown = Timer::from_seconds(SPIKE_INTERVAL, TimerMode::Once);
+        // the first step onto fresh spikes always hurts
+        cooldown.tick(cooldown.duration());
+
+        Self {
+            kind: TrapKind::Spikes,
+            position,
+            damage: 15.0,
+            hidden,
+            cooldown,
+            pressed: false,
+        }
+    }
+
+    pub fn pressure_plate(position: IVec2, launcher: IVec2) -> Self {
+        Self {
+            kind: TrapKind::PressurePlate { launcher },
+            position,
+            damage: 20.0,
+            hidden: false,
+            cooldown: Timer::from_seconds(0.0, TimerMode::Once),
+            pressed: false,
+        }
+    }
+
+    pub fn glyph(&self) -> char {
+        match self.kind {
+            TrapKind::Spikes => '^',
+            TrapKind::PressurePlate { .. } => '_',
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self.kind {
+            TrapKind::Spikes => "Spikes",
+            TrapKind::PressurePlate { .. } => "Pressure plate",
+        }
+    }
+
+    pub fn color(&self) -> Color {
+        match self.kind {
+            TrapKind::Spikes => Color::linear_rgb(0.8, 0.1, 0.1),
+            TrapKind::PressurePlate { .. } => Color::linear_rgb(0.6, 0.6, 0.4),
+        }
+    }
+}
+
+impl Visual for Trap {
+    fn cell(&self) -> IVec2 {
+        self.position
+    }
+
+    fn restyle(&self, renderable: &mut Renderable) {
+        renderable.glyph = self.glyph();
+        renderable.fg = self.color();
+        renderable.name = Some(self.name());
+    }
+
+    fn hidden(&self) -> bool {
+        self.hidden
+    }
+}
+
+/// flies in a straight line until it hits a wall, the player or an enemy
+#[derive(Component)]
+#[require(Renderable = Renderable::new('-', Color::linear_rgb(0.7, 0.7, 0.7), RenderLayer::Projectiles).named("Dart"))]
+pub struct Dart {
+    pub position: IVec2,
+    pub precise_position: Vec2,
+    pub direction: IVec2,
+    pub speed: f32,
+    pub damage: f32,
+    pub lifetime: f32,
+}
+
+impl Dart {
+    pub fn new(position: IVec2, direction: IVec2, damage: f32) -> Self {
+        Self {
+            position,
+            precise_position: position.as_vec2(),
+            direction,
+            speed: 25.0,
+            damage,
+            lifetime: 3.0,
+        }
+    }
+
+    pub fn glyph(&self) -> char {
+        if self.direction.x == 0 { '|' } else { '-' }
+    }
+}
+
+impl Visual for Dart {
+    fn cell(&self) -> IVec2 {
+        self.position
+    }
+
+    fn restyle(&self, renderable: &mut Renderable) {
+        renderable.glyph = self.glyph();
+    }
+}
@@ -1,4 +1,120 @@
-use bevy::prelude::{Component, IVec2, Resource};
+use bevy::prelude::*;
+
+/// environment variable that turns shaking, hit-stop and screen flashes off, like "1"
+const REDUCED_MOTION_VAR: &str = "ASCII_SURVIVORS_REDUCED_MOTION";
+
+/// damage in one hit that's big enough to freeze the game for a moment
+const BIG_HIT_DAMAGE: f32 = 20.0;
 
 #[derive(Resource, Component)]
 pub struct CameraOffset(pub IVec2);
+
+/// a tint washed over the whole screen, fading out as its timer runs down
+pub struct ScreenFlash {
+    pub color: Color,
+    pub timer: Timer,
+}
+
+/// Short lived camera feedback, layered on top of [`CameraOffset`] while drawing rather than
+/// moving the camera itself: shaking the view, freezing the game for a moment on big hits,
+/// and flashing a tint over the screen. Timed on real time, so it keeps going through a freeze.
+#[derive(Resource)]
+pub struct CameraEffects {
+    /// off for players who'd rather not have the screen shake and flash, which also stops
+    /// struck entities flashing inverted. flipped in game with [`CameraEffects::toggle`]
+    pub enabled: bool,
+    /// how hard the view shakes, from 0 to 1
+    pub trauma: f32,
+    /// added to the camera offset while drawing, rerolled every frame while shaking
+    pub shake_offset: IVec2,
+    /// real seconds left before a hit-stop lets go
+    pub hit_stop_seconds: f32,
+    /// whether a hit-stop paused virtual time, so only a pause it made gets undone
+    pub frozen: bool,
+    pub flash: Option<ScreenFlash>,
+}
+
+impl Default for CameraEffects {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            trauma: 0.0,
+            shake_offset: IVec2::ZERO,
+            hit_stop_seconds: 0.0,
+            frozen: false,
+            flash: None,
+        }
+    }
+}
+
+impl CameraEffects {
+    /// the defaults, turned off if `ASCII_SURVIVORS_REDUCED_MOTION` is set to anything but 0
+    pub fn from_env() -> Self {
+        let reduced_motion = std::env::var(REDUCED_MOTION_VAR)
+            .is_ok_and(|value| !matches!(value.trim(), "" | "0" | "false"));
+        Self {
+            enabled: !reduced_motion,
+            ..default()
+        }
+    }
+
+    /// turns the effects on or off, dropping anything still playing. a freeze in progress
+    /// is kept track of so it's still let go
+    pub fn toggle(&mut self) {
+        *self = Self {
+            enabled: !self.enabled,
+            frozen: self.frozen,
+            ..default()
+        };
+    }
+
+    /// where the world is drawn this frame
+    pub fn view_offset(&self, camera_offset: &CameraOffset) -> IVec2 {
+        camera_offset.0 + self.shake_offset
+    }
+
+    /// adds to the shake, up to full strength
+    pub fn shake(&mut self, trauma: f32) {
+        if self.enabled {
+            self.trauma = (self.trauma + trauma).min(1.0);
+        }
+    }
+
+    /// freezes the game for at least this many real seconds
+    pub fn hit_stop(&mut self, seconds: f32) {
+        if self.enabled {
+            self.hit_stop_seconds = self.hit_stop_seconds.max(seconds);
+        }
+    }
+
+    pub fn flash(&mut self, color: Color, seconds: f32) {
+        if self.enabled {
+            self.flash = Some(ScreenFlash {
+                color,
+                timer: Timer::from_seconds(seconds, TimerMode::Once),
+            });
+        }
+    }
+
+    /// the flash's color with its alpha scaled by how much of it is left
+    pub fn flash_tint(&self) -> Option<LinearRgba> {
+        let flash = self.flash.as_ref()?;
+        let color = LinearRgba::from(flash.color);
+        Some(color.with_alpha(color.alpha * flash.timer.fraction_remaining()))
+    }
+
+    /// the player was hurt, harder hits shake more and the biggest freeze the game
+    pub fn player_hit(&mut self, damage: f32) {
+        self.shake((damage / 50.0).clamp(0.2, 0.6));
+        self.flash(Color::linear_rgba(1.0, 0.0, 0.0, 0.35), 0.15);
+        if damage >= BIG_HIT_DAMAGE {
+            self.hit_stop(0.05);
+        }
+    }
+
+    pub fn boss_defeated(&mut self) {
+        self.shake(1.0);
+        self.hit_stop(0.13);
+        self.flash(Color::linear_rgba(1.0, 1.0, 0.9, 0.5), 0.4);
+    }
+}
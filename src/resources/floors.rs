@@ -0,0 +1,54 @@
+use crate::{
+    maps::{Map, Stairs},
+    resources::Level,
+};
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+/// The floors of the current level. Floors the player has left are stashed with their map
+/// and their disabled entities, so they come back exactly as they were left.
+#[derive(Resource, Default)]
+pub struct Floors {
+    /// the floor the player is on, every level starts on floor 0
+    pub current: usize,
+    /// set while the player is on their way onto another floor
+    pub arriving: Option<FloorArrival>,
+    stashed: HashMap<usize, StashedFloor>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct FloorArrival {
+    /// the way the player went, they come out on stairs leading back
+    pub direction: Stairs,
+    /// the floor's objects still have to be spawned from its markers
+    pub first_visit: bool,
+}
+
+pub struct StashedFloor {
+    pub map: Map,
+    pub entities: Vec<Entity>,
+}
+
+impl Floors {
+    pub fn stash(&mut self, floor: usize, map: Map, entities: Vec<Entity>) {
+        self.stashed.insert(floor, StashedFloor { map, entities });
+    }
+
+    pub fn take(&mut self, floor: usize) -> Option<StashedFloor> {
+        self.stashed.remove(&floor)
+    }
+
+    /// back to the first floor of a new level, returns the stashed entities to despawn
+    pub fn reset(&mut self) -> Vec<Entity> {
+        self.current = 0;
+        self.arriving = None;
+        self.stashed
+            .drain()
+            .flat_map(|(_, floor)| floor.entities)
+            .collect()
+    }
+
+    pub fn is_last(&self, level: Level) -> bool {
+        self.current + 1 >= level.floor_count()
+    }
+}
@@ -9,4 +9,6 @@ pub enum GameState {
     Game,
     LevelTransition,
     GameOver,
+    /// dev only, see `debug::MapEditorPlugin`
+    Editor,
 }
@@ -0,0 +1,7 @@
+use bevy::prelude::*;
+
+/// Whether the key to the glyphs on screen is drawn over the game.
+#[derive(Resource, Default)]
+pub struct Legend {
+    pub visible: bool,
+}
@@ -7,4 +7,41 @@ pub enum Level {
     Rest,
     Grassland,
     Dungeon,
+    Cave,
+}
+
+impl Level {
+    pub const ALL: [Level; 5] = [
+        Level::Survival,
+        Level::Rest,
+        Level::Grassland,
+        Level::Dungeon,
+        Level::Cave,
+    ];
+
+    /// REXPaint file backing this level, relative to the assets folder.
+    /// procedurally generated levels have none.
+    pub fn map_path(&self) -> Option<&'static str> {
+        match self {
+            Level::Survival => Some("maps/survival.xp"),
+            Level::Rest => Some("maps/rest.xp"),
+            Level::Grassland => Some("maps/grassland.xp"),
+            Level::Dungeon | Level::Cave => None,
+        }
+    }
+
+    /// how many floors deep the level goes, the portal only opens on the last one
+    pub fn floor_count(&self) -> usize {
+        match self {
+            Level::Dungeon => 3,
+            Level::Cave => 2,
+            Level::Survival | Level::Rest | Level::Grassland => 1,
+        }
+    }
+
+    /// whether the level's map has to mark where its portal opens. the rest area never
+    /// opens one, and survival opens it wherever the player has wandered off to
+    pub fn needs_portal_marker(&self) -> bool {
+        !matches!(self, Level::Rest | Level::Survival)
+    }
 }
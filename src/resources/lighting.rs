@@ -10,6 +10,12 @@ pub struct LightingOverlay {
     pub pixel_scale: u32,
     pub ambient_color: LinearRgba,
     pub buffer: Vec<LinearRgba>,
+    /// light each on-screen cell received last frame, ambient light left out
+    pub cell_light: Vec<LinearRgba>,
+    /// the camera offset `cell_light` was gathered with
+    pub cell_offset: IVec2,
+    /// terminal cells to leave out of the lighting this frame, counted from the top left
+    pub unlit_cells: Vec<UVec2>,
 }
 
 impl LightingOverlay {
@@ -20,7 +26,81 @@ impl LightingOverlay {
         )
     }
 
+    /// resizes the buffer for a terminal of a new size, every pixel goes back to ambient
+    pub fn resize(&mut self, size: UVec2) {
+        self.size = size;
+        self.texture_size = size * self.pixel_scale;
+        self.buffer =
+            vec![self.ambient_color; (self.texture_size.x * self.texture_size.y) as usize];
+        self.cell_light.clear();
+    }
+
     pub fn clear(&mut self) {
         self.buffer.fill(self.ambient_color);
     }
+
+    /// keeps a terminal cell clear of light and shadow this frame, for text that has to read
+    /// clearly over the dark
+    pub fn leave_unlit(&mut self, cell: UVec2) {
+        self.unlit_cells.push(cell);
+    }
+
+    /// clears the pixels of the cells left unlit, once the light has been gathered
+    pub fn punch_unlit_cells(&mut self) {
+        let scale = self.pixel_scale as usize;
+        let (width, _) = self.buffer_dimensions();
+        for cell in std::mem::take(&mut self.unlit_cells) {
+            if cell.x >= self.size.x || cell.y >= self.size.y {
+                continue;
+            }
+            let top_row = cell.y as usize * scale;
+            for row in top_row..top_row + scale {
+                let start = row * width + cell.x as usize * scale;
+                self.buffer[start..start + scale].fill(LinearRgba::NONE);
+            }
+        }
+    }
+
+    /// averages the pixel buffer down to one light value per cell, for gameplay to read
+    pub fn gather_cell_light(&mut self, camera_offset: IVec2) {
+        let scale = self.pixel_scale as usize;
+        let (width, _) = self.buffer_dimensions();
+        let pixels_per_cell = (scale * scale) as f32;
+
+        self.cell_light.clear();
+        for y in 0..self.size.y as usize {
+            // the buffer's rows run top to bottom, cells bottom to top like the world
+            let top_row = (self.size.y as usize - 1 - y) * scale;
+            for x in 0..self.size.x as usize {
+                let mut total = LinearRgba::NONE;
+                for row in top_row..top_row + scale {
+                    let start = row * width + x * scale;
+                    for pixel in &self.buffer[start..start + scale] {
+                        total += *pixel - self.ambient_color;
+                    }
+                }
+                self.cell_light.push(total / pixels_per_cell);
+            }
+        }
+        self.cell_offset = camera_offset;
+    }
+
+    /// the light that reached a world cell last frame, `None` for cells that were off screen
+    pub fn light_at(&self, position: IVec2) -> Option<LinearRgba> {
+        let cell = position + self.cell_offset;
+        let on_screen = cell.x >= 0
+            && cell.y >= 0
+            && (cell.x as u32) < self.size.x
+            && (cell.y as u32) < self.size.y;
+        if !on_screen {
+            return None;
+        }
+        self.cell_light
+            .get(cell.y as usize * self.size.x as usize + cell.x as usize)
+            .copied()
+    }
 }
+
+/// The sprite the lighting overlay is drawn onto.
+#[derive(Component)]
+pub struct LightingOverlaySprite;
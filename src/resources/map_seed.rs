@@ -0,0 +1,11 @@
+use bevy::prelude::Resource;
+
+/// Seed for procedurally generated levels, the same seed reproduces the same layouts.
+#[derive(Resource)]
+pub struct MapSeed(pub u64);
+
+impl Default for MapSeed {
+    fn default() -> Self {
+        Self(rand::random())
+    }
+}
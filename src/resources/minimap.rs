@@ -0,0 +1,13 @@
+use bevy::prelude::*;
+
+/// Whether the minimap is drawn in the corner of the terminal.
+#[derive(Resource)]
+pub struct Minimap {
+    pub visible: bool,
+}
+
+impl Default for Minimap {
+    fn default() -> Self {
+        Self { visible: true }
+    }
+}
@@ -1,19 +1,29 @@
 pub mod camera;
 pub mod channels;
+pub mod floors;
 pub mod game_state;
 pub mod kill_count;
+pub mod legend;
 pub mod level;
 pub mod lighting;
+pub mod map_seed;
+pub mod minimap;
 pub mod ruleset;
 pub mod scene_lock;
 pub mod timers;
+pub mod viewport;
 
 pub use camera::*;
 pub use channels::*;
+pub use floors::*;
 pub use game_state::*;
 pub use kill_count::*;
+pub use legend::*;
 pub use level::*;
 pub use lighting::*;
+pub use map_seed::*;
+pub use minimap::*;
 pub use ruleset::*;
 pub use scene_lock::*;
 pub use timers::*;
+pub use viewport::*;
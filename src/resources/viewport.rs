@@ -0,0 +1,63 @@
+use bevy::prelude::*;
+
+/// environment variable that pins the terminal to a fixed grid, like "80x50"
+const FIXED_SIZE_VAR: &str = "ASCII_SURVIVORS_TERMINAL";
+
+/// How big the terminal grid is. Unless the size is pinned, the grid fills the window with
+/// glyphs drawn `zoom` times the size of the font's.
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct ViewportConfig {
+    pub fixed_size: Option<UVec2>,
+    /// screen pixels per font pixel
+    pub zoom: u32,
+    /// pixel size of one glyph of the terminal font, the default px437 font is 8x8
+    pub glyph_pixels: UVec2,
+    /// the hud, minimap and scenes need at least this much room
+    pub min_size: UVec2,
+    /// caps the lighting texture and per-frame redraws on very large windows
+    pub max_size: UVec2,
+}
+
+impl Default for ViewportConfig {
+    fn default() -> Self {
+        Self {
+            fixed_size: None,
+            zoom: 2,
+            glyph_pixels: UVec2::splat(8),
+            min_size: UVec2::new(60, 36),
+            max_size: UVec2::new(160, 100),
+        }
+    }
+}
+
+impl ViewportConfig {
+    /// the defaults, with the grid pinned if `ASCII_SURVIVORS_TERMINAL` asks for it
+    pub fn from_env() -> Self {
+        let fixed_size = std::env::var(FIXED_SIZE_VAR).ok().and_then(|value| {
+            let size = parse_size(&value);
+            if size.is_none() {
+                warn!("ignoring {FIXED_SIZE_VAR}={value:?}, expected something like 80x50");
+            }
+            size
+        });
+        Self {
+            fixed_size,
+            ..default()
+        }
+    }
+
+    /// the grid size for a window of the given physical size
+    pub fn terminal_size(&self, window_pixels: UVec2) -> UVec2 {
+        let size = self.fixed_size.unwrap_or_else(|| {
+            let glyph = self.glyph_pixels * self.zoom.max(1);
+            window_pixels / glyph.max(UVec2::ONE)
+        });
+        size.clamp(self.min_size, self.max_size)
+    }
+}
+
+fn parse_size(value: &str) -> Option<UVec2> {
+    let (width, height) = value.trim().split_once(['x', 'X'])?;
+    let size = UVec2::new(width.trim().parse().ok()?, height.trim().parse().ok()?);
+    size.cmpgt(UVec2::ZERO).all().then_some(size)
+}
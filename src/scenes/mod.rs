@@ -9,9 +9,6 @@ use bevy_ascii_terminal::Terminal;
 
 use crate::resources::{FadeTimer, GameState, LoadingTimer};
 
-const TERMINAL_WIDTH: usize = 80;
-const TERMINAL_HEIGHT: usize = 50;
-
 pub struct GameScenesPlugin;
 
 impl Plugin for GameScenesPlugin {
@@ -65,6 +62,7 @@ impl SceneMarker {
     }
 }
 
+/// scene rows count from the middle row of the terminal, so scenes stay centered at any size
 #[derive(Component, Reflect, Clone)]
 #[reflect(Component)]
 pub struct SceneText {
@@ -93,12 +91,10 @@ pub struct SceneProgressBar {
     pub label_row: i32,
 }
 
-#[derive(Component, Reflect, Clone)]
+/// covers the whole terminal, whatever its size
+#[derive(Component, Reflect, Clone, Default)]
 #[reflect(Component)]
-pub struct SceneFadeOverlay {
-    pub width: usize,
-    pub height: usize,
-}
+pub struct SceneFadeOverlay;
 
 #[derive(Resource, Default)]
 pub struct SceneAssets {
@@ -296,9 +292,9 @@ fn render_scene_overlays(
 
     terminal.clear();
 
-    if let Some(overlay) = fade_query.iter().next() {
+    if !fade_query.is_empty() {
         if let Some(progress) = fade_timer.as_ref().map(|timer| timer.0.fraction()) {
-            render_fade_overlay(&mut terminal, overlay, progress);
+            render_fade_overlay(&mut terminal, progress);
         }
     }
 
@@ -314,9 +310,10 @@ fn render_scene_overlays(
 }
 
 fn render_scene_text(terminal: &mut Terminal, text: &SceneText) {
-    let row = text.row.max(0) as usize;
+    let size = terminal.size();
+    let row = scene_row(size, text.row);
     let column = if text.centered {
-        ((TERMINAL_WIDTH as i32 - text.value.len() as i32) / 2).max(0) as usize
+        centered_column(size, text.value.len())
     } else {
         text.column.max(0) as usize
     };
@@ -324,22 +321,24 @@ fn render_scene_text(terminal: &mut Terminal, text: &SceneText) {
 }
 
 fn render_progress_bar(terminal: &mut Terminal, bar: &SceneProgressBar, progress: f32) {
-    let row = bar.row.max(0) as usize;
-    let start_x = ((TERMINAL_WIDTH as i32 - bar.width as i32) / 2).max(0) as usize;
-    let filled = (progress.clamp(0.0, 1.0) * bar.width as f32) as usize;
+    let size = terminal.size();
+    let row = scene_row(size, bar.row);
+    let width = bar.width.min(size.x as usize);
+    let start_x = centered_column(size, width);
+    let filled = (progress.clamp(0.0, 1.0) * width as f32) as usize;
 
-    for x in 0..bar.width {
+    for x in 0..width {
         let ch = if x < filled { '#' } else { '-' };
-        terminal.put_char([start_x + x, row], ch);
+        terminal.put_string([start_x + x, row], ch.to_string());
     }
 
     let percent = format!("{:.0}%", (progress * 100.0).clamp(0.0, 100.0));
-    let percent_column = ((TERMINAL_WIDTH as i32 - percent.len() as i32) / 2).max(0) as usize;
-    let label_row = bar.label_row.max(0) as usize;
+    let percent_column = centered_column(size, percent.len());
+    let label_row = scene_row(size, bar.label_row);
     terminal.put_string([percent_column, label_row], percent.as_str());
 }
 
-fn render_fade_overlay(terminal: &mut Terminal, overlay: &SceneFadeOverlay, progress: f32) {
+fn render_fade_overlay(terminal: &mut Terminal, progress: f32) {
     let fade_char = if progress < 0.3 {
         '#'
     } else if progress < 0.6 {
@@ -351,15 +350,16 @@ fn render_fade_overlay(terminal: &mut Terminal, overlay: &SceneFadeOverlay, prog
     };
 
     let coverage = 1.0 - progress;
-    let width = overlay.width.max(1) as f32;
-    let height = overlay.height.max(1) as f32;
+    let size = terminal.size();
+    let width = size.x.max(1) as f32;
+    let height = size.y.max(1) as f32;
 
     let center_x = width / 2.0;
     let center_y = height / 2.0;
     let max_distance = (center_x.powi(2) + center_y.powi(2)).sqrt();
 
-    for y in 0..overlay.height {
-        for x in 0..overlay.width {
+    for y in 0..size.y as usize {
+        for x in 0..size.x as usize {
             let dx = x as f32 - center_x;
             let dy = y as f32 - center_y;
             let distance = (dx * dx + dy * dy).sqrt();
@@ -372,28 +372,37 @@ fn render_fade_overlay(terminal: &mut Terminal, overlay: &SceneFadeOverlay, prog
 
     if progress < 0.8 {
         let text = "Starting...";
-        let column = ((TERMINAL_WIDTH as i32 - text.len() as i32) / 2).max(0) as usize;
-        let row = (TERMINAL_HEIGHT / 2) as usize;
-        terminal.put_string([column, row], text);
+        let column = centered_column(size, text.len());
+        terminal.put_string([column, scene_row(size, 0)], text);
     }
 }
 
+/// the terminal row `offset` rows below the middle one, kept on screen
+fn scene_row(terminal_size: UVec2, offset: i32) -> usize {
+    let middle = terminal_size.y as i32 / 2;
+    (middle + offset).clamp(0, terminal_size.y as i32 - 1) as usize
+}
+
+fn centered_column(terminal_size: UVec2, width: usize) -> usize {
+    ((terminal_size.x as i32 - width as i32) / 2).max(0) as usize
+}
+
 fn build_loading_scene(type_registry: &AppTypeRegistry) -> DynamicScene {
     build_dynamic_scene(type_registry, |world| {
         world.spawn((
             SceneMarker::new(SceneId::Loading),
-            SceneText::centered(20, "ASCII SURVIVORS"),
+            SceneText::centered(-5, "ASCII SURVIVORS"),
         ));
         world.spawn((
             SceneMarker::new(SceneId::Loading),
-            SceneText::centered(25, "Loading..."),
+            SceneText::centered(0, "Loading..."),
         ));
         world.spawn((
             SceneMarker::new(SceneId::Loading),
             SceneProgressBar {
-                row: 27,
+                row: 2,
                 width: 40,
-                label_row: 29,
+                label_row: 4,
             },
         ));
     })
@@ -403,28 +412,22 @@ fn build_menu_scene(type_registry: &AppTypeRegistry) -> DynamicScene {
     build_dynamic_scene(type_registry, |world| {
         world.spawn((
             SceneMarker::new(SceneId::Menu),
-            SceneText::centered(15, "ASCII SURVIVORS"),
+            SceneText::centered(-10, "ASCII SURVIVORS"),
         ));
         world.spawn((
             SceneMarker::new(SceneId::Menu),
-            SceneText::centered(25, "[ PLAY ]"),
+            SceneText::centered(0, "[ PLAY ]"),
         ));
         world.spawn((
             SceneMarker::new(SceneId::Menu),
-            SceneText::centered(30, "Press SPACE or ENTER to start"),
+            SceneText::centered(5, "Press SPACE or ENTER to start"),
         ));
     })
 }
 
 fn build_fade_scene(type_registry: &AppTypeRegistry) -> DynamicScene {
     build_dynamic_scene(type_registry, |world| {
-        world.spawn((
-            SceneMarker::new(SceneId::FadeIn),
-            SceneFadeOverlay {
-                width: TERMINAL_WIDTH,
-                height: TERMINAL_HEIGHT,
-            },
-        ));
+        world.spawn((SceneMarker::new(SceneId::FadeIn), SceneFadeOverlay));
     })
 }
 
@@ -432,11 +435,11 @@ fn build_level_transition_scene(type_registry: &AppTypeRegistry) -> DynamicScene
     build_dynamic_scene(type_registry, |world| {
         world.spawn((
             SceneMarker::new(SceneId::LevelTransition),
-            SceneText::centered(25, "Level Transition..."),
+            SceneText::centered(0, "Level Transition..."),
         ));
         world.spawn((
             SceneMarker::new(SceneId::LevelTransition),
-            SceneText::centered(27, "Entering new area..."),
+            SceneText::centered(2, "Entering new area..."),
         ));
     })
 }
@@ -445,15 +448,15 @@ fn build_game_over_scene(type_registry: &AppTypeRegistry) -> DynamicScene {
     build_dynamic_scene(type_registry, |world| {
         world.spawn((
             SceneMarker::new(SceneId::GameOver),
-            SceneText::centered(20, "YOU DIED!"),
+            SceneText::centered(-5, "YOU DIED!"),
         ));
         world.spawn((
             SceneMarker::new(SceneId::GameOver),
-            SceneText::centered(25, "Press R to Restart"),
+            SceneText::centered(0, "Press R to Restart"),
         ));
         world.spawn((
             SceneMarker::new(SceneId::GameOver),
-            SceneText::centered(27, "Press ESC to return to Menu"),
+            SceneText::centered(2, "Press ESC to return to Menu"),
         ));
     })
 }
@@ -61,6 +61,7 @@ impl Arcanum {
                             max_lifetime: 3.0,
                         },
                         Fireball,
+                        Fireball::renderable(),
                     ));
                     Ok(())
                 } else {
@@ -86,14 +87,14 @@ impl Arcanum {
         }
     }
 
-    fn get_spell_mana_cost(&self, spell_type: SpellType) -> f32 {
+    pub fn get_spell_mana_cost(&self, spell_type: SpellType) -> f32 {
         match spell_type {
             SpellType::Fireball => 20.0,
             SpellType::MagicMissile => 15.0,
         }
     }
 
-    fn get_spell_name(&self, spell_type: SpellType) -> &'static str {
+    pub fn get_spell_name(&self, spell_type: SpellType) -> &'static str {
         match spell_type {
             SpellType::Fireball => "Fireball",
             SpellType::MagicMissile => "Magic Missile",
@@ -1,14 +1,17 @@
 use bevy::prelude::*;
 
-use crate::{effects::*, objects::*, resources::*};
+use crate::{maps::*, objects::*, resources::*, systems::hurt_player};
 
 pub fn boss_ai(
     mut commands: Commands,
     mut boss_query: Query<&mut Boss>,
     mut player_query: Query<(Entity, &mut Player)>,
-    time: Res<Time>,
-    mut timer: ResMut<EnemyMovementTimer>,
-    mut damage_effect_timer: ResMut<DamageEffectTimer>,
+    (time, mut timer): (Res<Time>, ResMut<EnemyMovementTimer>),
+    (mut damage_effect_timer, mut camera_effects): (
+        ResMut<DamageEffectTimer>,
+        ResMut<CameraEffects>,
+    ),
+    map: Option<Res<Map>>,
 ) {
     timer.0.tick(time.delta());
 
@@ -52,8 +55,12 @@ pub fn boss_ai(
                 },
             );
 
-            if move_direction != IVec2::ZERO {
-                let wish_move = head_position + move_direction;
+            let step = slide_step(move_direction, |step| {
+                map.as_ref().is_none_or(|map| can_step(map, &boss, step))
+            });
+
+            if let Some(step) = step {
+                let wish_move = head_position + step;
                 move_boss(&mut boss, wish_move);
 
                 // if any part of the boss touches the player, deal damage
@@ -68,11 +75,28 @@ pub fn boss_ai(
     }
 
     if player_damage_taken > 0.0 {
-        player.health -= player_damage_taken;
-        commands.entity(player_entity).insert(StatusEffect {
-            color: Color::linear_rgb(1.0, 0.0, 0.0),
-        });
-        damage_effect_timer.0.reset();
+        hurt_player(
+            &mut commands,
+            player_entity,
+            &mut player,
+            player_damage_taken,
+            map.as_deref(),
+            &mut damage_effect_timer,
+            &mut camera_effects,
+        );
+    }
+}
+
+/// snakes only need room for their head, the rest of the body follows its trail.
+/// giants move as one block, so every segment has to land on walkable ground
+fn can_step(map: &Map, boss: &Boss, step: IVec2) -> bool {
+    let walkable = |position: IVec2| map.is_walkable(position.x, position.y);
+    match boss.boss_type {
+        BossType::Snake => walkable(boss.get_head_position() + step),
+        BossType::Giant => boss
+            .segments
+            .iter()
+            .all(|segment| walkable(segment.position + step)),
     }
 }
 
@@ -1,8 +1,8 @@
 use bevy::prelude::*;
 use bevy_ascii_terminal::*;
-use rand::Rng;
+use rand::prelude::*;
 
-use crate::{objects::*, resources::*};
+use crate::{maps::*, objects::*, resources::*, systems::enemy_spawn_position};
 
 pub fn spawn_bosses(
     mut commands: Commands,
@@ -13,6 +13,8 @@ pub fn spawn_bosses(
     terminal_query: Query<&Terminal>,
     camera_offset: Res<CameraOffset>,
     game_state: Res<State<GameState>>,
+    marker_query: Query<&MapMarker>,
+    map: Option<Res<Map>>,
 ) {
     if survival_timer.0.elapsed_secs() >= ruleset.portal_spawn_time
         || *game_state.get() == GameState::LevelTransition
@@ -23,30 +25,26 @@ pub fn spawn_bosses(
     if let Ok(terminal) = terminal_query.single() {
         timer.0.tick(time.delta());
         if timer.0.finished() {
-            let size = terminal.size();
             let mut rng = rand::rng();
 
             // todo: figure out when a boss should spawn
             if rng.random_bool(0.25) {
-                let (x, y) = match rng.random_range(0..4) {
-                    // top edge
-                    0 => (rng.random_range(0..size[0] as i32), 0),
-                    // bottom edge
-                    1 => (rng.random_range(0..size[0] as i32), size[1] as i32 - 1),
-                    // left edge
-                    2 => (0, rng.random_range(0..size[1] as i32)),
-                    // right edge
-                    _ => (size[0] as i32 - 1, rng.random_range(0..size[1] as i32)),
-                };
-
-                let boss_type = match rng.random_range(0..2) {
-                    0 => BossType::Snake,
-                    _ => BossType::Giant,
+                let (boss_type, category) = match rng.random_range(0..2) {
+                    0 => (BossType::Snake, SpawnCategory::Snake),
+                    _ => (BossType::Giant, SpawnCategory::Giant),
                 };
 
                 // ensure the boss spawns offscreen
-                let spawn_position = IVec2::new(x, y) - camera_offset.0;
-                commands.spawn((Boss::new(spawn_position, boss_type),));
+                if let Some(spawn_position) = enemy_spawn_position(
+                    &mut rng,
+                    &marker_query,
+                    map.as_deref(),
+                    category,
+                    terminal.size(),
+                    camera_offset.0,
+                ) {
+                    commands.spawn((Boss::new(spawn_position, boss_type),));
+                }
             }
         }
     }
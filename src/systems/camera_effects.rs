@@ -0,0 +1,69 @@
+use crate::resources::*;
+use bevy::prelude::*;
+use rand::prelude::*;
+
+/// turns shaking, hit-stop and flashes on or off mid-game
+const CAMERA_EFFECTS_KEY: KeyCode = KeyCode::KeyV;
+
+/// trauma lost every second, a full strength shake settles in under a second
+const TRAUMA_DECAY: f32 = 1.5;
+
+/// cells the view is thrown around by at full trauma
+const MAX_SHAKE: f32 = 2.0;
+
+pub fn toggle_camera_effects(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut effects: ResMut<CameraEffects>,
+) {
+    if keyboard_input.just_pressed(CAMERA_EFFECTS_KEY) {
+        effects.toggle();
+        info!(
+            "screen effects {}",
+            if effects.enabled { "on" } else { "off" }
+        );
+    }
+}
+
+/// Lets shakes settle and flashes fade, and holds the game still through a hit-stop by
+/// pausing virtual time. Runs on real time and in every state, so a freeze can't outlive
+/// the game it started in.
+pub fn update_camera_effects(
+    mut effects: ResMut<CameraEffects>,
+    real_time: Res<Time<Real>>,
+    mut virtual_time: ResMut<Time<Virtual>>,
+) {
+    let delta = real_time.delta();
+
+    effects.trauma = (effects.trauma - TRAUMA_DECAY * delta.as_secs_f32()).max(0.0);
+    // squaring keeps small shakes subtle while big ones still kick
+    let reach = (effects.trauma * effects.trauma * MAX_SHAKE).round() as i32;
+    let shake_offset = if reach > 0 {
+        let mut rng = rand::rng();
+        IVec2::new(
+            rng.random_range(-reach..=reach),
+            rng.random_range(-reach..=reach),
+        )
+    } else {
+        IVec2::ZERO
+    };
+    effects.shake_offset = shake_offset;
+
+    if let Some(flash) = effects.flash.as_mut() {
+        flash.timer.tick(delta);
+        if flash.timer.finished() {
+            effects.flash = None;
+        }
+    }
+
+    // a pause someone else made is left alone, and outlasts the hit-stop
+    effects.hit_stop_seconds = (effects.hit_stop_seconds - delta.as_secs_f32()).max(0.0);
+    if effects.hit_stop_seconds > 0.0 {
+        if !effects.frozen && !virtual_time.is_paused() {
+            virtual_time.pause();
+            effects.frozen = true;
+        }
+    } else if effects.frozen {
+        virtual_time.unpause();
+        effects.frozen = false;
+    }
+}
@@ -18,3 +18,17 @@ pub fn despawn_portals(mut commands: Commands, portal_query: Query<Entity, With<
         commands.entity(entity).despawn();
     }
 }
+
+// does an entity belong to the current level?
+#[derive(Component)]
+pub struct LevelScoped;
+
+// despawn everything that was placed for the previous level
+pub fn despawn_level_entities(
+    mut commands: Commands,
+    level_query: Query<Entity, With<LevelScoped>>,
+) {
+    for entity in level_query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
@@ -1,14 +1,17 @@
-use crate::{effects::*, objects::*, resources::*};
+use crate::{maps::*, objects::*, resources::*, systems::hurt_player};
 use bevy::prelude::*;
 
 pub fn enemy_ai(
     mut commands: Commands,
     mut enemy_query: Query<&mut Enemy>,
     mut player_query: Query<(Entity, &mut Player)>,
-    time: Res<Time>,
-    mut timer: ResMut<EnemyMovementTimer>,
-    mut damage_effect_timer: ResMut<DamageEffectTimer>,
+    (time, mut timer): (Res<Time>, ResMut<EnemyMovementTimer>),
+    (mut damage_effect_timer, mut camera_effects): (
+        ResMut<DamageEffectTimer>,
+        ResMut<CameraEffects>,
+    ),
     _scene_lock: Res<SceneLock>,
+    (map, flow_field): (Option<Res<Map>>, Res<FlowField>),
 ) {
     timer.0.tick(time.delta());
 
@@ -25,7 +28,11 @@ pub fn enemy_ai(
     let mut player_damage_taken = 0.0;
 
     for mut enemy in enemy_query.iter_mut() {
-        let direction_to_player = (player_world_pos - enemy.position).as_vec2();
+        // follow the flow field around walls, or head straight for the player off the field
+        let direction_to_player = match flow_field.step(enemy.position) {
+            Some(step) => step.as_vec2(),
+            None => (player_world_pos - enemy.position).as_vec2(),
+        };
         let attraction_force = if direction_to_player.length() > 0.0 {
             direction_to_player.normalize() * enemy.speed
         } else {
@@ -69,8 +76,15 @@ pub fn enemy_ai(
                 },
             );
 
-            if move_direction != IVec2::ZERO {
-                let wish_move = enemy.position + move_direction;
+            let position = enemy.position;
+            let step = slide_step(move_direction, |step| {
+                let target = position + step;
+                map.as_ref()
+                    .is_none_or(|map| map.is_walkable(target.x, target.y))
+            });
+
+            if let Some(step) = step {
+                let wish_move = position + step;
 
                 // check if the desired position is occupied by another enemy
                 let mut is_occupied = false;
@@ -96,10 +110,14 @@ pub fn enemy_ai(
     }
 
     if player_damage_taken > 0.0 {
-        player.health -= player_damage_taken;
-        commands.entity(player_entity).insert(StatusEffect {
-            color: Color::linear_rgb(1.0, 0.0, 0.0),
-        });
-        damage_effect_timer.0.reset();
+        hurt_player(
+            &mut commands,
+            player_entity,
+            &mut player,
+            player_damage_taken,
+            map.as_deref(),
+            &mut damage_effect_timer,
+            &mut camera_effects,
+        );
     }
 }
@@ -1,4 +1,4 @@
-use crate::{objects::*, resources::*};
+use crate::{maps::*, objects::*, resources::*};
 use bevy::prelude::*;
 use bevy_ascii_terminal::*;
 use rand::prelude::*;
@@ -12,6 +12,8 @@ pub fn spawn_enemies(
     terminal_query: Query<&Terminal>,
     camera_offset: Res<CameraOffset>,
     game_state: Res<State<GameState>>,
+    marker_query: Query<&MapMarker>,
+    map: Option<Res<Map>>,
 ) {
     if survival_timer.0.elapsed_secs() >= ruleset.portal_spawn_time
         || *game_state.get() == GameState::LevelTransition
@@ -22,24 +24,74 @@ pub fn spawn_enemies(
     if let Ok(terminal) = terminal_query.single() {
         timer.0.tick(time.delta());
         if timer.0.finished() {
-            let size = terminal.size();
             let mut rng = rand::rng();
+            if let Some(spawn_position) = enemy_spawn_position(
+                &mut rng,
+                &marker_query,
+                map.as_deref(),
+                SpawnCategory::Horde,
+                terminal.size(),
+                camera_offset.0,
+            ) {
+                commands.spawn((Enemy::new(spawn_position),));
+            }
+        }
+    }
+}
+
+/// how many random edge positions we try before giving up on a spawn
+const SPAWN_ATTEMPTS: usize = 8;
+
+/// picks one of the map's enemy spawn markers, or a random spot just inside the edge of the screen.
+/// positions the map's zones don't allow for this category, or that can't be walked on, are skipped.
+pub fn enemy_spawn_position(
+    rng: &mut ThreadRng,
+    marker_query: &Query<&MapMarker>,
+    map: Option<&Map>,
+    category: SpawnCategory,
+    size: UVec2,
+    camera_offset: IVec2,
+) -> Option<IVec2> {
+    let allowed = |position: IVec2| {
+        map.is_none_or(|map| {
+            map.is_walkable(position.x, position.y) && map.allows_spawn(position, category)
+        })
+    };
 
-            // choose a random edge to spawn the enemy at
-            let (x, y) = match rng.random_range(0..4) {
-                // top edge
-                0 => (rng.random_range(0..size[0] as i32), 0),
-                // bottom edge
-                1 => (rng.random_range(0..size[0] as i32), size[1] as i32 - 1),
-                // left edge
-                2 => (0, rng.random_range(0..size[1] as i32)),
-                // right edge
-                _ => (size[0] as i32 - 1, rng.random_range(0..size[1] as i32)),
-            };
-
-            // spawn the enemy offscreen
-            let spawn_position = IVec2::new(x, y) - camera_offset.0;
-            commands.spawn((Enemy::new(spawn_position),));
+    let spawn_points: Vec<IVec2> = marker_query
+        .iter()
+        .filter(|marker| marker.kind == MarkerKind::EnemySpawn)
+        .map(|marker| marker.position)
+        .collect();
+
+    // a level with spawn markers only ever spawns enemies on them
+    if !spawn_points.is_empty() {
+        let allowed_points: Vec<IVec2> = spawn_points
+            .into_iter()
+            .filter(|position| allowed(*position))
+            .collect();
+        return allowed_points.choose(rng).copied();
+    }
+
+    for _ in 0..SPAWN_ATTEMPTS {
+        // choose a random edge to spawn the enemy at
+        let (x, y) = match rng.random_range(0..4) {
+            // top edge
+            0 => (rng.random_range(0..size[0] as i32), 0),
+            // bottom edge
+            1 => (rng.random_range(0..size[0] as i32), size[1] as i32 - 1),
+            // left edge
+            2 => (0, rng.random_range(0..size[1] as i32)),
+            // right edge
+            _ => (size[0] as i32 - 1, rng.random_range(0..size[1] as i32)),
+        };
+
+        // spawn the enemy offscreen
+        let position = IVec2::new(x, y) - camera_offset;
+        if allowed(position) {
+            return Some(position);
         }
     }
+
+    None
 }
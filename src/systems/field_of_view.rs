@@ -0,0 +1,22 @@
+use crate::{maps::*, objects::*};
+use bevy::prelude::*;
+
+/// recomputes the player's field of view whenever they step onto a new cell,
+/// or when the map itself changes (a new level, a door opening)
+pub fn update_field_of_view(
+    player_query: Query<&Player>,
+    map: Option<ResMut<Map>>,
+    mut last_origin: Local<Option<IVec2>>,
+) {
+    let (Ok(player), Some(mut map)) = (player_query.single(), map) else {
+        return;
+    };
+
+    let origin = player.world_position;
+    if *last_origin == Some(origin) && !map.is_changed() {
+        return;
+    }
+
+    *last_origin = Some(origin);
+    compute_fov(&mut map, origin, SIGHT_RADIUS);
+}
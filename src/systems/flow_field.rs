@@ -0,0 +1,20 @@
+use crate::{maps::*, objects::*};
+use bevy::prelude::*;
+
+/// rebuilds the enemies' flow field once the player reaches a new cell, or the map changes
+pub fn update_flow_field(
+    player_query: Query<&Player>,
+    map: Option<Res<Map>>,
+    mut flow_field: ResMut<FlowField>,
+) {
+    let (Ok(player), Some(map)) = (player_query.single(), map) else {
+        return;
+    };
+
+    let target = player.world_position;
+    if flow_field.target == Some(target) && !map.is_changed() {
+        return;
+    }
+
+    *flow_field = FlowField::build(&map, target);
+}
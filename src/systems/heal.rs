@@ -12,7 +12,14 @@ pub fn heal_player_system(
     if let Ok((player_entity, mut player)) = player_query.single_mut() {
         for (entity, _) in campfire_query.iter() {
             if player.health < player.max_health {
+                let healed = player.max_health - player.health;
                 player.health = player.max_health;
+                DamageNumber::spawn(
+                    &mut commands,
+                    player.world_position,
+                    healed,
+                    DamageKind::Heal,
+                );
 
                 audio
                     .play(asset_server.load("sfx/heal.ogg"))
@@ -20,6 +27,7 @@ pub fn heal_player_system(
 
                 commands.entity(entity).remove::<Message>();
                 commands.entity(player_entity).insert(StatusEffect {
+                    name: "Healed",
                     color: Color::linear_rgb(0.0, 1.0, 0.0),
                 });
             }
@@ -0,0 +1,197 @@
+use crate::{effects::StatusEffect, objects::*, resources::*, systems::*};
+use bevy::prelude::*;
+use bevy_ascii_terminal::{Terminal, string::TerminalString};
+
+/// rows the hud panel takes up along the bottom of the terminal
+pub const HUD_ROWS: u32 = 3;
+
+const PANEL_COLOR: Color = Color::linear_rgb(0.02, 0.02, 0.03);
+const LABEL_COLOR: Color = Color::linear_rgb(0.7, 0.7, 0.7);
+const HEALTH_COLOR: Color = Color::linear_rgb(0.0, 1.0, 0.1);
+const MANA_COLOR: Color = Color::linear_rgb(0.3, 0.4, 1.0);
+const EXPERIENCE_COLOR: Color = Color::linear_rgb(0.9, 0.75, 0.1);
+const SPELL_COLOR: Color = Color::linear_rgb(1.0, 0.6, 0.2);
+const SPELL_WAITING_COLOR: Color = Color::linear_rgb(0.4, 0.4, 0.4);
+
+/// Where each part of the hud goes on a terminal of a given size. The panel runs along the
+/// bottom, split into a left half for the player's bars and a right half for everything else.
+struct HudLayout {
+    /// top row of the panel, in `put_string` coordinates
+    top: usize,
+    /// first column of the right half
+    right: usize,
+    /// glyphs in each resource bar, so a bar and its label fill most of a half
+    bar_length: usize,
+    width: usize,
+}
+
+impl HudLayout {
+    fn new(terminal_size: UVec2) -> Self {
+        let width = terminal_size.x as usize;
+        let half = width / 2;
+        Self {
+            top: terminal_size.y.saturating_sub(HUD_ROWS) as usize,
+            right: half + 1,
+            bar_length: half.saturating_sub(14).clamp(4, 30),
+            width,
+        }
+    }
+
+    fn row(&self, index: usize) -> usize {
+        self.top + index
+    }
+
+    /// column just past a bar drawn from `x`, with a label `label_length` long
+    fn after_bar(&self, x: usize, label_length: usize) -> usize {
+        x + label_length + 1 + self.bar_length + 1
+    }
+}
+
+/// Draws the player's health, mana and experience, their level, kills and keys, each learned
+/// spell with how long until it's cast again, and any status effects on them. Panel cells are
+/// kept out of the lighting so the hud reads clearly in the dark.
+pub fn render_hud(
+    player_query: Query<(&Player, Option<&StatusEffect>)>,
+    mut terminal_query: Query<&mut Terminal>,
+    mut overlay: Option<ResMut<LightingOverlay>>,
+    kill_count: Res<KillCount>,
+    spell_timer: Res<SpellInputTimer>,
+    (survival_timer, ruleset, level): (Res<SurvivalTimer>, Res<Ruleset>, Res<Level>),
+) {
+    let (Ok((player, status_effect)), Ok(mut terminal)) =
+        (player_query.single(), terminal_query.single_mut())
+    else {
+        return;
+    };
+    let terminal_size = terminal.size();
+    let layout = HudLayout::new(terminal_size);
+
+    // clear the panel so the map doesn't show through between the text
+    let mut blank = TerminalString::from(" ".repeat(layout.width));
+    blank.decoration.bg_color = Some(LinearRgba::from(PANEL_COLOR));
+    for index in 0..HUD_ROWS as usize {
+        terminal.put_string([0, layout.row(index)], blank.clone());
+    }
+    if let Some(overlay) = overlay.as_mut() {
+        for y in layout.top as u32..terminal_size.y {
+            for x in 0..terminal_size.x {
+                overlay.leave_unlit(UVec2::new(x, y));
+            }
+        }
+    }
+
+    let arcanum = &player.arcanum;
+    let health = player.health.max(0.0) as usize;
+    let max_health = player.max_health as usize;
+    let mana = arcanum.mana as usize;
+    let max_mana = arcanum.max_mana as usize;
+    // (label, column, row, current, max, color, text after the bar)
+    let bars = [
+        (
+            "HP",
+            0,
+            0,
+            health,
+            max_health,
+            HEALTH_COLOR,
+            format!("{health}/{max_health}"),
+        ),
+        (
+            "MP",
+            layout.right,
+            0,
+            mana,
+            max_mana,
+            MANA_COLOR,
+            format!("{mana}/{max_mana}"),
+        ),
+        (
+            "XP",
+            0,
+            1,
+            player.experience as usize,
+            player.experience_to_next_level as usize,
+            EXPERIENCE_COLOR,
+            format!("Lvl {}", player.level),
+        ),
+    ];
+    for (name, x, row, current, max, color, suffix) in bars {
+        let y = layout.row(row);
+        draw_resource_bar(
+            &mut terminal,
+            ResourceBarConfig {
+                resource_name: name,
+                filled_char: '#',
+                empty_char: '-',
+                bar_length: layout.bar_length,
+                current_value: current,
+                max_value: max,
+                bar_color: color,
+                bar_x_position: x,
+                bar_y_position: y,
+            },
+        );
+        put_text(
+            &mut terminal,
+            &layout,
+            [layout.after_bar(x, name.len()), y],
+            &suffix,
+            Color::WHITE,
+        );
+    }
+
+    put_text(
+        &mut terminal,
+        &layout,
+        [layout.right, layout.row(1)],
+        &format!("Kills {}  Keys {}", kill_count.enemies, player.keys),
+        LABEL_COLOR,
+    );
+
+    // every learned spell goes off together whenever the cast timer comes around
+    let mut x = 0;
+    for &spell in &arcanum.spells {
+        let (state, color) = if arcanum.mana < arcanum.get_spell_mana_cost(spell) {
+            ("no mana".to_string(), SPELL_WAITING_COLOR)
+        } else {
+            (
+                format!("{:.1}s", spell_timer.0.remaining_secs()),
+                SPELL_COLOR,
+            )
+        };
+        let text = format!("{} {state}", arcanum.get_spell_name(spell));
+        put_text(&mut terminal, &layout, [x, layout.row(2)], &text, color);
+        x += text.len() + 2;
+    }
+
+    if let Some(effect) = status_effect {
+        put_text(
+            &mut terminal,
+            &layout,
+            [layout.right, layout.row(2)],
+            effect.name,
+            effect.color,
+        );
+    }
+
+    if matches!(level.as_ref(), Level::Survival) {
+        draw_survival_timer(&mut terminal, survival_timer.0.elapsed_secs(), &ruleset);
+    }
+}
+
+/// writes text on the panel, cut short rather than wrapped where it runs off the terminal
+fn put_text(
+    terminal: &mut Terminal,
+    layout: &HudLayout,
+    [x, y]: [usize; 2],
+    text: &str,
+    color: Color,
+) {
+    let room = layout.width.saturating_sub(x);
+    if room == 0 {
+        return;
+    }
+    let mut text = TerminalString::from(text.chars().take(room).collect::<String>());
+    text.decoration.fg_color = Some(LinearRgba::from(color));
+    terminal.put_string([x, y], text);
+}
@@ -1,4 +1,5 @@
 use crate::{
+    maps::*,
     objects::{Interaction, *},
     resources::*,
 };
@@ -6,18 +7,39 @@ use bevy::prelude::*;
 
 const INTERACTION_KEY: KeyCode = KeyCode::KeyE;
 
+/// what the player can interact with, and the parts of it that change when they do
+type Interactables<'w, 's> = (
+    Query<'w, 's, (Entity, &'static Interaction, &'static GlobalTransform)>,
+    Query<'w, 's, &'static mut Door>,
+    Query<'w, 's, &'static mut Brazier>,
+    Query<'w, 's, &'static mut Mirror>,
+);
+
+/// everything besides the player that can stand in a doorway and keep it from closing
+type DoorOccupants<'w, 's> = (
+    Query<'w, 's, &'static Enemy>,
+    Query<'w, 's, &'static Boss>,
+    Query<'w, 's, &'static DoorKey>,
+    Query<'w, 's, &'static Orb>,
+    Query<'w, 's, &'static Breakable>,
+);
+
 pub fn interaction_system(
-    keyboard_input: Res<ButtonInput<KeyCode>>,
-    player_query: Query<&Player>,
-    interaction_query: Query<(Entity, &Interaction, &GlobalTransform)>,
+    mut player_query: Query<&mut Player>,
+    (interaction_query, mut door_query, mut brazier_query, mut mirror_query): Interactables,
+    (enemy_query, boss_query, key_query, orb_query, breakable_query): DoorOccupants,
+    mut map: Option<ResMut<Map>>,
     mut commands: Commands,
     kill_count: Res<KillCount>,
-    mut interaction_timer: ResMut<InteractionTimer>,
-    time: Res<Time>,
+    (keyboard_input, mut interaction_timer, time): (
+        Res<ButtonInput<KeyCode>>,
+        ResMut<InteractionTimer>,
+        Res<Time>,
+    ),
 ) {
     interaction_timer.0.tick(time.delta());
     if keyboard_input.just_pressed(INTERACTION_KEY) && interaction_timer.0.finished() {
-        if let Ok(player) = player_query.single() {
+        if let Ok(mut player) = player_query.single_mut() {
             let interaction_distance = 1.0;
             for (entity, interaction, transform) in interaction_query.iter() {
                 let interaction_position = transform.translation();
@@ -25,15 +47,50 @@ pub fn interaction_system(
                 let distance = interaction_position.truncate().distance(player_position);
                 if distance <= interaction_distance {
                     match interaction.interaction_type {
+                        InteractionType::Brazier => {
+                            if let Ok(mut brazier) = brazier_query.get_mut(entity)
+                                && !brazier.lit
+                            {
+                                brazier.lit = true;
+                                commands.entity(entity).insert((
+                                    Brazier::light(),
+                                    Message::new("The brazier catches.".to_string(), 2.0),
+                                ));
+                            }
+                        }
                         InteractionType::Campfire => {
                             commands
                                 .entity(entity)
                                 .insert(Message::new("You feel rested.".to_string(), 2.0));
                         }
+                        InteractionType::Door => {
+                            let occupants: Vec<IVec2> = enemy_query
+                                .iter()
+                                .map(|enemy| enemy.position)
+                                .chain(boss_query.iter().flat_map(|boss| {
+                                    boss.segments.iter().map(|segment| segment.position)
+                                }))
+                                .chain(key_query.iter().map(|key| key.position))
+                                .chain(orb_query.iter().map(|orb| orb.position))
+                                .chain(breakable_query.iter().map(|breakable| breakable.position))
+                                .collect();
+                            if let (Ok(mut door), Some(map)) =
+                                (door_query.get_mut(entity), map.as_deref_mut())
+                                && let Some(message) =
+                                    use_door(&mut door, &mut player, map, &occupants)
+                            {
+                                commands.entity(entity).insert(Message::new(message, 2.0));
+                            }
+                        }
                         InteractionType::LeaderboardNpc => {
                             let message = format!("Enemies killed: {}", kill_count.enemies);
                             commands.entity(entity).insert(Message::new(message, 2.0));
                         }
+                        InteractionType::Mirror => {
+                            if let Ok(mut mirror) = mirror_query.get_mut(entity) {
+                                mirror.turn();
+                            }
+                        }
                         InteractionType::ShopNpc => {
                             commands
                                 .entity(entity)
@@ -46,3 +103,35 @@ pub fn interaction_system(
         }
     }
 }
+
+/// opens or closes a door, using up one of the player's keys if it's locked. an open door
+/// stays open while the player or any of the `occupants` stands in it.
+/// returns what to tell the player, if anything
+fn use_door(
+    door: &mut Door,
+    player: &mut Player,
+    map: &mut Map,
+    occupants: &[IVec2],
+) -> Option<String> {
+    let mut message = None;
+    if door.locked {
+        if player.keys == 0 {
+            return Some("It's locked.".to_string());
+        }
+        player.keys -= 1;
+        door.locked = false;
+        message = Some("The key turns in the lock.".to_string());
+    }
+
+    // nobody gets shut inside a doorway
+    let position = door.position;
+    let occupied = player.world_position == position || occupants.contains(&position);
+    let tile = map.get_tile_mut(position.x, position.y)?;
+    tile.tile_type = match tile.tile_type {
+        TileType::DOOR => TileType::OPEN_DOOR,
+        TileType::OPEN_DOOR if !occupied => TileType::DOOR,
+        other => other,
+    };
+
+    message
+}
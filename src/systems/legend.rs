@@ -0,0 +1,201 @@
+use crate::{maps::*, objects::*, resources::*, systems::*};
+use bevy::prelude::*;
+use bevy_ascii_terminal::{GridSize, Terminal, string::TerminalString};
+use std::collections::HashSet;
+
+const LEGEND_KEY: KeyCode = KeyCode::KeyL;
+
+const NAME_COLOR: Color = Color::linear_rgb(0.8, 0.8, 0.8);
+
+pub fn toggle_legend(keyboard_input: Res<ButtonInput<KeyCode>>, mut legend: ResMut<Legend>) {
+    if keyboard_input.just_pressed(LEGEND_KEY) {
+        legend.visible = !legend.visible;
+    }
+}
+
+/// one line of the legend
+struct LegendEntry {
+    glyph: char,
+    color: Color,
+    name: String,
+}
+
+/// Lists every glyph on screen with its color and what it is, in a box in the top left
+/// corner. Built each frame from the renderables in view and the tiles drawn under them, so
+/// it only ever explains what the player can actually see.
+pub fn render_legend(
+    legend: Res<Legend>,
+    renderable_query: Query<(&Renderable, &GridPosition, &Visibility)>,
+    map: Option<Res<Map>>,
+    camera_offset: Res<CameraOffset>,
+    camera_effects: Res<CameraEffects>,
+    mut terminal_query: Query<&mut Terminal>,
+    mut overlay: Option<ResMut<LightingOverlay>>,
+) {
+    let Ok(mut terminal) = terminal_query.single_mut() else {
+        return;
+    };
+    if !legend.visible {
+        return;
+    }
+    let terminal_size = terminal.size();
+    let entries = collect_entries(
+        &renderable_query,
+        map.as_deref(),
+        camera_effects.view_offset(&camera_offset),
+        terminal_size,
+    );
+
+    // keep clear of the hud, and say how many didn't fit
+    let rows = terminal_size.y.saturating_sub(HUD_ROWS + 2) as usize;
+    if rows < 2 {
+        return;
+    }
+    let hidden = entries.len().saturating_sub(rows);
+    let shown = if hidden > 0 { rows - 1 } else { entries.len() };
+    let more = format!("+{} more", entries.len() - shown);
+
+    let inner_width = entries[..shown]
+        .iter()
+        .map(|entry| entry.name.chars().count() + 4)
+        .chain([more.len() + 2, "Legend".len() + 2])
+        .max()
+        .unwrap_or_default()
+        .min(terminal_size.x.saturating_sub(2) as usize);
+    let frame_size = IVec2::new(
+        inner_width as i32 + 2,
+        rows.min(entries.len().max(1)) as i32 + 2,
+    );
+
+    let mut blank = TerminalString::from(" ".repeat(inner_width));
+    blank.decoration.bg_color = Some(LinearRgba::BLACK);
+    for y in 1..frame_size.y - 1 {
+        terminal.put_string([1, y], blank.clone());
+    }
+    draw_frame(&mut terminal, IVec2::ZERO, frame_size);
+    put_text(&mut terminal, [2, 0], "Legend", NAME_COLOR, inner_width);
+    if let Some(overlay) = overlay.as_mut() {
+        for x in 0..frame_size.x {
+            for y in 0..frame_size.y {
+                overlay.leave_unlit(UVec2::new(x as u32, y as u32));
+            }
+        }
+    }
+
+    if entries.is_empty() {
+        put_text(
+            &mut terminal,
+            [2, 1],
+            "nothing",
+            NAME_COLOR,
+            inner_width - 1,
+        );
+        return;
+    }
+    for (row, entry) in entries[..shown].iter().enumerate() {
+        let y = row + 1;
+        put_text(
+            &mut terminal,
+            [2, y],
+            &entry.glyph.to_string(),
+            entry.color,
+            1,
+        );
+        put_text(
+            &mut terminal,
+            [5, y],
+            &entry.name,
+            NAME_COLOR,
+            inner_width - 4,
+        );
+    }
+    if hidden > 0 {
+        put_text(&mut terminal, [2, rows], &more, NAME_COLOR, inner_width - 1);
+    }
+}
+
+/// named renderables in view, topmost layers first, then the tiles drawn on screen. each
+/// glyph and name pair is listed once
+fn collect_entries(
+    renderable_query: &Query<(&Renderable, &GridPosition, &Visibility)>,
+    map: Option<&Map>,
+    camera_offset: IVec2,
+    terminal_size: UVec2,
+) -> Vec<LegendEntry> {
+    let on_screen = |position: IVec2| {
+        let screen = world_to_screen(position + camera_offset, terminal_size);
+        terminal_size.contains_point([screen.x, screen.y])
+    };
+    let in_view = |position: IVec2| map.is_none_or(|map| map.is_visible(position));
+
+    let mut renderables: Vec<_> = renderable_query
+        .iter()
+        .filter(|(_, position, visibility)| {
+            **visibility != Visibility::Hidden && in_view(position.0) && on_screen(position.0)
+        })
+        .filter_map(|(renderable, ..)| renderable.name.map(|name| (renderable, name)))
+        .collect();
+    renderables.sort_by_key(|(renderable, _)| std::cmp::Reverse(renderable.layer));
+
+    let mut seen = HashSet::new();
+    let mut entries = Vec::new();
+    for (renderable, name) in renderables {
+        if seen.insert((renderable.glyph, name.to_string())) {
+            entries.push(LegendEntry {
+                glyph: renderable.glyph,
+                color: renderable.fg,
+                name: name.to_string(),
+            });
+        }
+    }
+
+    let Some(map) = map else {
+        return entries;
+    };
+    // tiles in the order they're registered, built-in ones first
+    let mut tile_types = HashSet::new();
+    for x in 0..terminal_size.x as i32 {
+        for y in 0..terminal_size.y as i32 {
+            let world_position = world_to_screen(IVec2::new(x, y), terminal_size) - camera_offset;
+            if let Some(tile) = map.get_tile(world_position.x, world_position.y)
+                && tile.explored
+            {
+                tile_types.insert(tile.tile_type);
+            }
+        }
+    }
+    for tile_type in map
+        .registry
+        .all()
+        .filter(|tile_type| tile_types.contains(tile_type))
+    {
+        let def = map.tile_def(tile_type);
+        let name = tile_name(&def.name);
+        if def.glyph != ' ' && seen.insert((def.glyph, name.clone())) {
+            entries.push(LegendEntry {
+                glyph: def.glyph,
+                color: def.color(),
+                name,
+            });
+        }
+    }
+    entries
+}
+
+/// "stairs_down" reads as "Stairs down"
+fn tile_name(name: &str) -> String {
+    let name = name.replace('_', " ");
+    let mut chars = name.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+/// writes text cut short to `width` glyphs, over the legend's black background
+fn put_text(terminal: &mut Terminal, [x, y]: [usize; 2], text: &str, color: Color, width: usize) {
+    let mut text = TerminalString::from(text.chars().take(width).collect::<String>());
+    text.decoration.fg_color = Some(LinearRgba::from(color));
+    text.decoration.bg_color = Some(LinearRgba::BLACK);
+    terminal.put_string([x, y], text);
+}
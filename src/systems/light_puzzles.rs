@@ -0,0 +1,145 @@
+use crate::{effects::ParticleEmitter, maps::*, objects::*, resources::*, systems::LevelScoped};
+use bevy::prelude::*;
+
+/// what a solved puzzle hands out at each of its reward markers
+const PUZZLE_REWARD: LootDrop = LootDrop::Orbs {
+    count: 5,
+    experience: 20,
+};
+
+/// lights braziers, runes and mirrors from the light that reached their cells last frame.
+/// pieces that were off screen keep whatever state they had
+pub fn update_light_puzzle_pieces(
+    mut commands: Commands,
+    overlay: Option<Res<LightingOverlay>>,
+    map: Option<Res<Map>>,
+    mut brazier_query: Query<(Entity, &mut Brazier)>,
+    mut rune_query: Query<&mut Rune>,
+    mut mirror_query: Query<(&mut Mirror, &mut LightEmitter)>,
+) {
+    let (Some(overlay), Some(map)) = (overlay, map) else {
+        return;
+    };
+    let firelight_at = |position: IVec2| overlay.light_at(position).map(firelight);
+
+    // braziers catch from nearby fire and never go out
+    for (entity, mut brazier) in brazier_query.iter_mut() {
+        if !brazier.lit
+            && firelight_at(brazier.position).is_some_and(|light| light >= FIRELIGHT_THRESHOLD)
+        {
+            brazier.lit = true;
+            commands.entity(entity).insert(Brazier::light());
+        }
+    }
+
+    for mut rune in rune_query.iter_mut() {
+        if let Some(light) = firelight_at(rune.position) {
+            rune.lit = light >= FIRELIGHT_THRESHOLD;
+        }
+    }
+
+    for (mut mirror, mut emitter) in mirror_query.iter_mut() {
+        // the light travels until something opaque stops it
+        let mut target = mirror.position;
+        for _ in 0..Mirror::REACH {
+            let next = target + mirror.facing;
+            let blocked = map
+                .get_tile(next.x, next.y)
+                .is_none_or(|tile| map.tile_def(tile.tile_type).blocks_light);
+            if blocked {
+                break;
+            }
+            target = next;
+        }
+        mirror.target = target;
+
+        if let Some(light) = firelight_at(mirror.position) {
+            mirror.lit = light >= FIRELIGHT_THRESHOLD;
+        }
+
+        // a mirror facing a nearby wall would only light itself back up
+        let clear = (target - mirror.position).as_vec2().length() > emitter.radius;
+        emitter.intensity = if mirror.lit && clear {
+            Mirror::LIGHT_INTENSITY
+        } else {
+            0.0
+        };
+    }
+}
+
+/// fires each puzzle the first time every brazier and rune in its zone is lit at once
+pub fn solve_light_puzzles(
+    mut commands: Commands,
+    mut puzzle_query: Query<(Entity, &mut LightPuzzle)>,
+    brazier_query: Query<&Brazier>,
+    rune_query: Query<&Rune>,
+    mut door_query: Query<&mut Door>,
+    mut map: Option<ResMut<Map>>,
+) {
+    // only borrowed mutably once a puzzle is solved, so the map isn't marked changed every frame
+    let Some(map) = map.as_mut() else {
+        return;
+    };
+
+    for (entity, mut puzzle) in puzzle_query.iter_mut() {
+        if puzzle.solved {
+            continue;
+        }
+
+        let pieces: Vec<bool> = brazier_query
+            .iter()
+            .filter(|brazier| puzzle.zone.contains(brazier.position))
+            .map(|brazier| brazier.lit)
+            .chain(
+                rune_query
+                    .iter()
+                    .filter(|rune| puzzle.zone.contains(rune.position))
+                    .map(|rune| rune.lit),
+            )
+            .collect();
+        if pieces.is_empty() || !pieces.iter().all(|&lit| lit) {
+            continue;
+        }
+        puzzle.solved = true;
+
+        for mut door in door_query.iter_mut() {
+            if puzzle.zone.contains(door.position) {
+                door.locked = false;
+                if let Some(tile) = map.get_tile_mut(door.position.x, door.position.y)
+                    && tile.tile_type == TileType::DOOR
+                {
+                    tile.tile_type = TileType::OPEN_DOOR;
+                }
+            }
+        }
+
+        let marked = |kind: MarkerKind| -> Vec<IVec2> {
+            map.markers
+                .iter()
+                .filter(|marker| marker.kind == kind && puzzle.zone.contains(marker.position))
+                .map(|marker| marker.position)
+                .collect()
+        };
+        let passages = marked(MarkerKind::Passage);
+        let rewards = marked(MarkerKind::PuzzleReward);
+
+        for position in passages {
+            if let Some(tile) = map.get_tile_mut(position.x, position.y) {
+                tile.tile_type = TileType::STONE;
+            }
+            ParticleEmitter::dust(position).burst(&mut commands, 10);
+        }
+        for position in rewards {
+            PUZZLE_REWARD.spawn(&mut commands, position, Some(map));
+            commands.spawn((
+                ParticleEmitter::sparkles(position, Color::linear_rgb(1.0, 0.8, 0.3), 1.5),
+                LevelScoped,
+            ));
+        }
+
+        commands.entity(entity).insert(Message::new(
+            "Something shifts in the dark.".to_string(),
+            2.0,
+        ));
+    }
+}
@@ -20,51 +20,89 @@ pub fn setup_lighting_overlay(
         .single()
         .expect("terminal entity should exist before lighting overlay setup");
     let size = terminal.size();
-    let logical_size = UVec2::new(size[0] as u32, size[1] as u32);
-    let texture_size = logical_size * LIGHTING_PIXEL_SCALE;
+    let texture_size = size * LIGHTING_PIXEL_SCALE;
     let pixel_count = (texture_size.x * texture_size.y) as usize;
 
-    let mut image = Image::new_fill(
-        Extent3d {
-            width: texture_size.x,
-            height: texture_size.y,
-            depth_or_array_layers: 1,
-        },
-        TextureDimension::D2,
-        &[0, 0, 0, 0],
-        TextureFormat::Rgba8UnormSrgb,
-        RenderAssetUsages::RENDER_WORLD | RenderAssetUsages::MAIN_WORLD,
-    );
-    image.texture_descriptor.usage |= TextureUsages::COPY_DST;
-
-    let handle = images.add(image);
+    let handle = images.add(overlay_image(texture_size));
     let ambient = LinearRgba::from(Color::srgba(0.01, 0.01, 0.02, 0.35));
 
     commands.insert_resource(LightingOverlay {
         handle: handle.clone(),
-        size: logical_size,
+        size,
         texture_size,
         pixel_scale: LIGHTING_PIXEL_SCALE,
         ambient_color: ambient,
         buffer: vec![ambient; pixel_count],
+        cell_light: Vec::new(),
+        cell_offset: IVec2::ZERO,
+        unlit_cells: Vec::new(),
     });
 
     let mut overlay_sprite = Sprite::from_image(handle.clone());
-    overlay_sprite.custom_size = Some(Vec2::new(size[0] as f32, size[1] as f32));
+    overlay_sprite.custom_size = Some(size.as_vec2());
 
     commands.spawn((
         overlay_sprite,
-        Transform::from_xyz(size[0] as f32 / 2.0, size[1] as f32 / 2.0, 5.0),
+        overlay_transform(size),
         Name::new("LightingOverlay"),
+        LightingOverlaySprite,
     ));
 }
 
+/// swaps in a texture of the new size once the terminal has been resized
+pub fn fit_lighting_overlay(
+    overlay: Option<ResMut<LightingOverlay>>,
+    mut images: ResMut<Assets<Image>>,
+    terminal_query: Query<&Terminal>,
+    mut sprite_query: Query<(&mut Sprite, &mut Transform), With<LightingOverlaySprite>>,
+) {
+    let (Some(mut overlay), Ok(terminal)) = (overlay, terminal_query.single()) else {
+        return;
+    };
+    let size = terminal.size();
+    if overlay.size == size {
+        return;
+    }
+
+    overlay.resize(size);
+    images.insert(&overlay.handle, overlay_image(overlay.texture_size));
+    for (mut sprite, mut transform) in sprite_query.iter_mut() {
+        sprite.custom_size = Some(size.as_vec2());
+        *transform = overlay_transform(size);
+    }
+}
+
+fn overlay_image(texture_size: UVec2) -> Image {
+    let mut image = Image::new_fill(
+        Extent3d {
+            width: texture_size.x,
+            height: texture_size.y,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &[0, 0, 0, 0],
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::RENDER_WORLD | RenderAssetUsages::MAIN_WORLD,
+    );
+    image.texture_descriptor.usage |= TextureUsages::COPY_DST;
+    image
+}
+
+/// the overlay covers the terminal, whose bottom left corner sits on the origin
+fn overlay_transform(size: UVec2) -> Transform {
+    let center = size.as_vec2() / 2.0;
+    Transform::from_xyz(center.x, center.y, 5.0)
+}
+
 pub fn update_lighting_overlay(
     mut overlay: ResMut<LightingOverlay>,
     mut images: ResMut<Assets<Image>>,
     camera_offset: Res<CameraOffset>,
+    camera_effects: Res<CameraEffects>,
     player_lights: Query<(&Player, &LightEmitter)>,
     campfire_lights: Query<(&Campfire, &LightEmitter)>,
+    brazier_lights: Query<(&Brazier, &LightEmitter)>,
+    mirror_lights: Query<(&Mirror, &LightEmitter)>,
     player_occluders: Query<&Player>,
     shop_occluders: Query<&ShopNpc>,
     enemy_occluders: Query<&Enemy>,
@@ -75,6 +113,7 @@ pub fn update_lighting_overlay(
     };
 
     overlay.clear();
+    let camera_offset = camera_effects.view_offset(&camera_offset);
 
     let width = overlay.texture_size.x as usize;
     let height = overlay.texture_size.y as usize;
@@ -84,7 +123,7 @@ pub fn update_lighting_overlay(
     for (player, emitter) in player_lights.iter() {
         let pos = screen_position(
             player.world_position,
-            camera_offset.0,
+            camera_offset,
             overlay_size,
             overlay_scale,
         );
@@ -98,8 +137,25 @@ pub fn update_lighting_overlay(
         );
     }
 
-    // Skip occluder collection unless there are campfire lights that need shadow casting.
-    if !campfire_lights.is_empty() {
+    // fires and the light mirrors throw all cast shadows
+    let placed_lights: Vec<(IVec2, &LightEmitter)> = campfire_lights
+        .iter()
+        .map(|(campfire, emitter)| (campfire.position, emitter))
+        .chain(
+            brazier_lights
+                .iter()
+                .map(|(brazier, emitter)| (brazier.position, emitter)),
+        )
+        .chain(
+            mirror_lights
+                .iter()
+                .map(|(mirror, emitter)| (mirror.target, emitter)),
+        )
+        .filter(|(_, emitter)| emitter.intensity > 0.0)
+        .collect();
+
+    // Skip occluder collection unless there are placed lights that need shadow casting.
+    if !placed_lights.is_empty() {
         let occluders = gather_occluders(
             &player_occluders,
             &shop_occluders,
@@ -107,18 +163,13 @@ pub fn update_lighting_overlay(
             &boss_occluders,
             overlay_size,
             overlay_scale,
-            camera_offset.0,
+            camera_offset,
         );
 
         let mut relevant_occluders: Vec<LightOccluder> = Vec::new();
 
-        for (campfire, emitter) in campfire_lights.iter() {
-            let pos = screen_position(
-                campfire.position,
-                camera_offset.0,
-                overlay_size,
-                overlay_scale,
-            );
+        for &(position, emitter) in &placed_lights {
+            let pos = screen_position(position, camera_offset, overlay_size, overlay_scale);
 
             let radius_pixels = emitter.radius * overlay_scale as f32;
             relevant_occluders.clear();
@@ -155,6 +206,15 @@ pub fn update_lighting_overlay(
         }
     }
 
+    overlay.gather_cell_light(camera_offset);
+    // flashes tint the world, the hud and anything else left unlit stays clear
+    if let Some(tint) = camera_effects.flash_tint() {
+        for color in overlay.buffer.iter_mut() {
+            *color = color.mix(&tint, tint.alpha);
+        }
+    }
+    overlay.punch_unlit_cells();
+
     if let Some(data) = image.data.as_mut() {
         for (idx, color) in overlay.buffer.iter().enumerate() {
             let data_index = idx * 4;
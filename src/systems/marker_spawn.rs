@@ -0,0 +1,244 @@
+use crate::{
+    effects::ParticleEmitter,
+    maps::*,
+    objects::{Interaction, *},
+    resources::*,
+    systems::{HUD_ROWS, LevelScoped},
+};
+use bevy::prelude::*;
+use bevy_ascii_terminal::Terminal;
+use rand::prelude::*;
+
+/// cells kept between the player and the screen's edge when a locked map has to scroll
+const LOCKED_SCROLL_MARGIN: i32 = 4;
+
+/// places the player and spawns the objects painted on the current map's marker layer
+pub fn spawn_map_markers(
+    mut commands: Commands,
+    map: Res<Map>,
+    mut player_query: Query<&mut Player>,
+    mut camera_offset: ResMut<CameraOffset>,
+    scene_lock: Res<SceneLock>,
+    terminal_query: Query<&Terminal>,
+) {
+    let Ok(terminal) = terminal_query.single() else {
+        return;
+    };
+    let terminal_size = terminal.size();
+
+    if let Ok(mut player) = player_query.single_mut() {
+        let start = map
+            .marker(MarkerKind::PlayerStart)
+            .unwrap_or(IVec2::new(map.width as i32 / 2, map.height as i32 / 2));
+        place_player(
+            &mut player,
+            &map,
+            start,
+            &mut camera_offset.0,
+            scene_lock.0,
+            terminal_size,
+        );
+        // keys only fit the level they were found on
+        player.keys = 0;
+    }
+
+    spawn_map_objects(&mut commands, &map);
+}
+
+/// puts the player on a cell of the current map, moving the camera along
+pub fn place_player(
+    player: &mut Player,
+    map: &Map,
+    position: IVec2,
+    camera_offset: &mut IVec2,
+    scene_lock: bool,
+    terminal_size: UVec2,
+) {
+    *camera_offset = camera_offset_for(map, position, scene_lock, None, terminal_size);
+    player.place(position, *camera_offset, terminal_size);
+}
+
+/// Where the camera sits with the player at `position`. Scrolling levels keep the player
+/// centered. Locked ones keep their map centered above the hud, and only scroll along an axis
+/// the map doesn't fit on, from `current` or the player if there's none, just far enough to
+/// keep the player on screen.
+pub fn camera_offset_for(
+    map: &Map,
+    position: IVec2,
+    scene_lock: bool,
+    current: Option<IVec2>,
+    terminal_size: UVec2,
+) -> IVec2 {
+    let size = terminal_size.as_ivec2();
+    let centered = IVec2::new(size.x / 2, size.y - 1 - size.y / 2) - position;
+    if !scene_lock {
+        return centered;
+    }
+
+    // screen cells left clear of the hud, counted up from the bottom like world space
+    let view_min = IVec2::new(0, (HUD_ROWS as i32).min(size.y - 1));
+    let view_size = size - view_min;
+    let map_size = IVec2::new(map.width as i32, map.height as i32);
+    let current = current.unwrap_or(centered);
+    let axis = |index: usize| {
+        let (low, length) = (view_min[index], view_size[index]);
+        if map_size[index] <= length {
+            return low + (length - map_size[index]) / 2;
+        }
+
+        // no screen past the map's edges, and the player kept a few cells clear of the screen's
+        let margin = LOCKED_SCROLL_MARGIN.min((length - 1) / 2);
+        current[index]
+            .clamp(low + length - map_size[index], low)
+            .clamp(
+                low + margin - position[index],
+                low + length - 1 - margin - position[index],
+            )
+    };
+    IVec2::new(axis(0), axis(1))
+}
+
+/// spawns the doors, puzzles and marked objects of a freshly built map
+pub fn spawn_map_objects(commands: &mut Commands, map: &Map) {
+    // doors are painted on the tile layer, a marker on the same cell locks them
+    for x in 0..map.width as i32 {
+        for y in 0..map.height as i32 {
+            if !map.tiles[x as usize][y as usize].tile_type.is_door() {
+                continue;
+            }
+
+            let position = IVec2::new(x, y);
+            let locked = map
+                .markers
+                .iter()
+                .any(|marker| marker.kind == MarkerKind::LockedDoor && marker.position == position);
+            commands.spawn((
+                Door::new(position, locked),
+                Interaction::new(InteractionType::Door),
+                Transform::from_xyz(x as f32, y as f32, 0.0),
+                LevelScoped,
+            ));
+        }
+    }
+
+    // each painted puzzle zone is solved on its own
+    for zone in map
+        .zones
+        .iter()
+        .filter(|zone| zone.kind == ZoneKind::Puzzle)
+    {
+        let anchor = zone.cells().into_iter().min_by_key(|cell| (cell.y, cell.x));
+        let anchor = anchor.unwrap_or_default();
+        commands.spawn((
+            LightPuzzle::new(zone.clone()),
+            Transform::from_xyz(anchor.x as f32, anchor.y as f32, 0.0),
+            LevelScoped,
+        ));
+    }
+
+    for marker in &map.markers {
+        let position = marker.position;
+        let transform = Transform::from_xyz(position.x as f32, position.y as f32, 0.0);
+
+        match marker.kind {
+            MarkerKind::Campfire => {
+                commands.spawn((
+                    Campfire::new(position),
+                    Interaction::new(InteractionType::Campfire), // todo: maybe we should reconsider naming it 'Interaction'
+                    LightEmitter::campfire(),
+                    LightFlicker::campfire(),
+                    ParticleEmitter::embers(position),
+                    transform,
+                    LevelScoped,
+                ));
+                // the logs under the fire
+                commands.spawn((
+                    Renderable::new('=', Color::linear_rgb(0.5, 0.25, 0.0), RenderLayer::Map)
+                        .named("Firewood"),
+                    GridPosition(position - IVec2::Y),
+                    LevelScoped,
+                ));
+            }
+            MarkerKind::ShopNpc => {
+                commands.spawn((
+                    ShopNpc::new(position),
+                    Interaction::new(InteractionType::ShopNpc),
+                    transform,
+                    LevelScoped,
+                ));
+            }
+            MarkerKind::LeaderboardNpc => {
+                commands.spawn((
+                    ShopNpc::new(position),
+                    Interaction::new(InteractionType::LeaderboardNpc),
+                    transform,
+                    LevelScoped,
+                ));
+            }
+            MarkerKind::Loot => {
+                let kind = *BreakableKind::ALL
+                    .choose(&mut rand::rng())
+                    .unwrap_or(&BreakableKind::Crate);
+                commands.spawn((Breakable::new(kind, position), LevelScoped));
+            }
+            MarkerKind::Spikes | MarkerKind::HiddenTrap => {
+                let hidden = marker.kind == MarkerKind::HiddenTrap;
+                commands.spawn((Trap::spikes(position, hidden), LevelScoped));
+            }
+            MarkerKind::Key => {
+                commands.spawn((DoorKey::new(position), LevelScoped));
+            }
+            MarkerKind::Brazier => {
+                commands.spawn((
+                    Brazier::new(position),
+                    Interaction::new(InteractionType::Brazier),
+                    transform,
+                    LevelScoped,
+                ));
+            }
+            MarkerKind::Rune => {
+                commands.spawn((Rune::new(position), LevelScoped));
+            }
+            MarkerKind::Mirror => {
+                commands.spawn((
+                    Mirror::new(position),
+                    Mirror::light(),
+                    Interaction::new(InteractionType::Mirror),
+                    transform,
+                    LevelScoped,
+                ));
+            }
+            MarkerKind::PressurePlate => {
+                // plates fire from the closest launcher in line with them
+                let launcher = map
+                    .markers
+                    .iter()
+                    .filter(|other| other.kind == MarkerKind::DartLauncher)
+                    .map(|other| other.position)
+                    .filter(|&other| other.x == position.x || other.y == position.y)
+                    .min_by_key(|&other| (other - position).abs().element_sum());
+
+                match launcher {
+                    Some(launcher) => {
+                        commands.spawn((Trap::pressure_plate(position, launcher), LevelScoped));
+                    }
+                    None => warn!(
+                        "pressure plate at {} on {} has no dart launcher in line with it",
+                        position, map.name
+                    ),
+                }
+            }
+            // the rest are decided on at runtime by the systems that read them
+            MarkerKind::PlayerStart
+            | MarkerKind::EnemySpawn
+            | MarkerKind::Portal
+            | MarkerKind::Item
+            | MarkerKind::DartLauncher
+            | MarkerKind::LockedDoor
+            | MarkerKind::Passage
+            | MarkerKind::PuzzleReward => {}
+        }
+
+        commands.spawn((*marker, LevelScoped));
+    }
+}
@@ -0,0 +1,197 @@
+use crate::{maps::*, objects::*, resources::*};
+use bevy::prelude::*;
+use bevy_ascii_terminal::{Terminal, string::TerminalString};
+use std::collections::HashMap;
+
+const MINIMAP_KEY: KeyCode = KeyCode::KeyM;
+
+/// glyphs inside the minimap's border
+const MINIMAP_SIZE: IVec2 = IVec2::new(20, 12);
+
+/// brightness of explored areas that are out of sight
+const REMEMBERED_BRIGHTNESS: f32 = 0.45;
+
+pub fn toggle_minimap(keyboard_input: Res<ButtonInput<KeyCode>>, mut minimap: ResMut<Minimap>) {
+    if keyboard_input.just_pressed(MINIMAP_KEY) {
+        minimap.visible = !minimap.visible;
+    }
+}
+
+/// draws the map shrunk to a few cells per glyph in the top right corner. only explored
+/// areas show up, and bosses only while they're in sight
+pub fn render_minimap(
+    minimap: Res<Minimap>,
+    map: Option<Res<Map>>,
+    player_query: Query<&Player>,
+    portal_query: Query<&Portal>,
+    boss_query: Query<&Boss>,
+    (shop_npc_query, campfire_query): (Query<&ShopNpc>, Query<&Campfire>),
+    mut terminal_query: Query<&mut Terminal>,
+) {
+    let (Some(map), Ok(player), Ok(mut terminal)) =
+        (map, player_query.single(), terminal_query.single_mut())
+    else {
+        return;
+    };
+    let terminal_size = terminal.size().as_ivec2();
+    let frame_size = MINIMAP_SIZE + IVec2::splat(2);
+    if !minimap.visible || terminal_size.x < frame_size.x || terminal_size.y < frame_size.y {
+        return;
+    }
+
+    // the whole map is shrunk to fit, endless terrain is followed around the player instead
+    let map_size = IVec2::new(map.width as i32, map.height as i32);
+    let scale = (map_size + MINIMAP_SIZE - IVec2::ONE) / MINIMAP_SIZE;
+    let scale = scale.max_element().max(1);
+    let shown = MINIMAP_SIZE * scale;
+    let origin = if map.terrain.is_some() {
+        player.world_position - shown / 2
+    } else {
+        (map_size - shown) / 2
+    };
+
+    // top left corner of the frame, in `put_string` coordinates
+    let corner = IVec2::new(terminal_size.x - frame_size.x, 0);
+    draw_frame(&mut terminal, corner, frame_size);
+
+    let mut glyphs: HashMap<IVec2, (char, LinearRgba)> = HashMap::new();
+    for x in 0..MINIMAP_SIZE.x {
+        for y in 0..MINIMAP_SIZE.y {
+            let block = IVec2::new(x, y);
+            if let Some(glyph) = summarize_block(&map, origin + block * scale, scale) {
+                glyphs.insert(block, glyph);
+            }
+        }
+    }
+
+    let block_of = |position: IVec2| {
+        let block = (position - origin).div_euclid(IVec2::splat(scale));
+        let inside = block.cmpge(IVec2::ZERO).all() && block.cmplt(MINIMAP_SIZE).all();
+        inside.then_some(block)
+    };
+    let explored = |position: IVec2| {
+        map.get_tile(position.x, position.y)
+            .is_some_and(|tile| tile.explored)
+    };
+
+    // later entries are drawn over earlier ones
+    let mut marks: Vec<(IVec2, char, Color)> = Vec::new();
+    for campfire in campfire_query
+        .iter()
+        .filter(|campfire| explored(campfire.position))
+    {
+        marks.push((campfire.position, '*', Color::linear_rgb(1.0, 0.6, 0.0)));
+    }
+    for npc in shop_npc_query.iter().filter(|npc| explored(npc.position)) {
+        marks.push((npc.position, 'N', Color::linear_rgb(0.3, 0.9, 0.3)));
+    }
+    for portal in portal_query
+        .iter()
+        .filter(|portal| explored(portal.position))
+    {
+        marks.push((portal.position, 'O', Color::linear_rgb(0.8, 0.3, 1.0)));
+    }
+    for boss in boss_query.iter() {
+        if let Some(segment) = boss
+            .segments
+            .iter()
+            .find(|segment| map.is_visible(segment.position))
+        {
+            marks.push((segment.position, 'B', Color::linear_rgb(1.0, 0.2, 0.2)));
+        }
+    }
+    marks.push((player.world_position, '@', Color::linear_rgb(1.0, 1.0, 0.3)));
+
+    for (position, glyph, color) in marks {
+        if let Some(block) = block_of(position) {
+            glyphs.insert(block, (glyph, LinearRgba::from(color)));
+        }
+    }
+
+    for x in 0..MINIMAP_SIZE.x {
+        for y in 0..MINIMAP_SIZE.y {
+            let block = IVec2::new(x, y);
+            let (glyph, color) = glyphs
+                .get(&block)
+                .copied()
+                .unwrap_or((' ', LinearRgba::BLACK));
+            // the map's y axis points up, the terminal's rows count down
+            let cell = corner + IVec2::new(1 + x, MINIMAP_SIZE.y - y);
+            put_glyph(&mut terminal, cell, glyph, color);
+        }
+    }
+}
+
+/// what a square block of map cells looks like on the minimap, `None` while it's unexplored.
+/// open ground wins over walls, so corridors don't vanish between them
+fn summarize_block(map: &Map, min: IVec2, scale: i32) -> Option<(char, LinearRgba)> {
+    let mut counts: HashMap<TileType, u32> = HashMap::new();
+    let mut stairs = None;
+    let mut visible = false;
+    for x in min.x..min.x + scale {
+        for y in min.y..min.y + scale {
+            let Some(tile) = map.get_tile(x, y).filter(|tile| tile.explored) else {
+                continue;
+            };
+            *counts.entry(tile.tile_type).or_default() += 1;
+            visible |= tile.visible;
+            if map.tile_def(tile.tile_type).stairs.is_some() {
+                stairs = Some(tile.tile_type);
+            }
+        }
+    }
+
+    let most_common = |walkable: bool| {
+        counts
+            .iter()
+            .filter(|(tile_type, _)| map.tile_def(**tile_type).walkable == walkable)
+            .max_by_key(|(tile_type, count)| (**count, map.tile_def(**tile_type).glyph))
+            .map(|(tile_type, _)| *tile_type)
+    };
+    let tile_type = stairs
+        .or_else(|| most_common(true))
+        .or_else(|| most_common(false))?;
+
+    // bare floor has no glyph or color of its own
+    let (glyph, color) = if tile_type == TileType::EMPTY {
+        ('.', LinearRgba::gray(0.4))
+    } else {
+        let def = map.tile_def(tile_type);
+        (def.glyph, LinearRgba::from(def.color()))
+    };
+    let color = if visible {
+        color
+    } else {
+        (color * REMEMBERED_BRIGHTNESS).with_alpha(1.0)
+    };
+    Some((glyph, color))
+}
+
+/// a box outline, `corner` is its top left in `put_string` coordinates
+pub fn draw_frame(terminal: &mut Terminal, corner: IVec2, size: IVec2) {
+    let color = LinearRgba::gray(0.6);
+    let last = size - IVec2::ONE;
+    for x in 0..size.x {
+        for y in 0..size.y {
+            let glyph = match (x, y) {
+                (0, 0) => '┌',
+                (x, 0) if x == last.x => '┐',
+                (0, y) if y == last.y => '└',
+                (x, y) if x == last.x && y == last.y => '┘',
+                (_, 0) => '─',
+                (_, y) if y == last.y => '─',
+                (0, _) => '│',
+                (x, _) if x == last.x => '│',
+                _ => continue,
+            };
+            put_glyph(terminal, corner + IVec2::new(x, y), glyph, color);
+        }
+    }
+}
+
+fn put_glyph(terminal: &mut Terminal, cell: IVec2, glyph: char, color: LinearRgba) {
+    let mut glyph = TerminalString::from(glyph.to_string());
+    glyph.decoration.fg_color = Some(color);
+    glyph.decoration.bg_color = Some(LinearRgba::BLACK);
+    terminal.put_string([cell.x, cell.y], glyph);
+}
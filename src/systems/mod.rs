@@ -1,37 +1,57 @@
 pub mod boss_ai;
 pub mod boss_spawn;
+pub mod camera_effects;
 pub mod campfire_animation;
 pub mod cleanup;
-pub mod ember_animation;
 pub mod enemy_ai;
 pub mod enemy_spawn;
+pub mod field_of_view;
+pub mod flow_field;
 pub mod heal;
+pub mod hud;
 pub mod interaction;
+pub mod legend;
 pub mod light_flicker;
+pub mod light_puzzles;
 pub mod lighting_overlay;
+pub mod marker_spawn;
 pub mod message;
+pub mod minimap;
 pub mod player_movement;
 pub mod portal_spawn;
 pub mod portal_transition;
 pub mod render;
-pub mod shop_npc_spawn;
 pub mod spell_casting;
+pub mod stairs;
+pub mod terrain_streaming;
+pub mod traps;
+pub mod viewport;
 
 pub use boss_ai::*;
 pub use boss_spawn::*;
+pub use camera_effects::*;
 pub use campfire_animation::*;
 pub use cleanup::*;
-pub use ember_animation::*;
 pub use enemy_ai::*;
 pub use enemy_spawn::*;
+pub use field_of_view::*;
+pub use flow_field::*;
 pub use heal::*;
+pub use hud::*;
 pub use interaction::*;
+pub use legend::*;
 pub use light_flicker::*;
+pub use light_puzzles::*;
 pub use lighting_overlay::*;
+pub use marker_spawn::*;
 pub use message::*;
+pub use minimap::*;
 pub use player_movement::*;
 pub use portal_spawn::*;
 pub use portal_transition::*;
 pub use render::*;
-pub use shop_npc_spawn::*;
 pub use spell_casting::*;
+pub use stairs::*;
+pub use terrain_streaming::*;
+pub use traps::*;
+pub use viewport::*;
@@ -1,6 +1,7 @@
-use crate::{maps::*, objects::*, resources::*};
+use crate::{maps::*, objects::*, resources::*, systems::camera_offset_for};
 use bevy::prelude::*;
 use bevy_ascii_terminal::*;
+use bevy_kira_audio::prelude::*;
 
 pub fn player_movement(
     mut player_query: Query<&mut Player>,
@@ -12,6 +13,8 @@ pub fn player_movement(
     terminal_query: Query<&Terminal>,
     scene_lock: Res<SceneLock>,
     map: Option<Res<Map>>,
+    asset_server: Res<AssetServer>,
+    audio: Res<AudioChannel<Sfx>>,
 ) {
     timer.0.tick(time.delta());
     if timer.0.finished()
@@ -19,8 +22,6 @@ pub fn player_movement(
         && let Ok(terminal) = terminal_query.single()
     {
         let size = terminal.size();
-        let center_x = size[0] as i32 / 2;
-        let center_y = size[1] as i32 / 2;
 
         let mut move_offset = IVec2::new(0, 0);
         for (_, gamepad) in &gamepad_input {
@@ -68,29 +69,32 @@ pub fn player_movement(
             move_offset.x += 1;
         }
 
-        if scene_lock.0 {
-            let new_pos = player.position + move_offset.clamp(IVec2::new(-1, -1), IVec2::new(1, 1));
+        // movement is checked against the map in world space, where y points up
+        let clamped = move_offset.clamp(IVec2::new(-1, -1), IVec2::new(1, 1));
+        let step = IVec2::new(clamped.x, -clamped.y);
 
-            if let Some(map) = &map {
-                if map.is_walkable(new_pos.x, new_pos.y) {
-                    player.position = new_pos;
-                    player.world_position =
-                        IVec2::new(player.position.x, size[1] as i32 - 1 - player.position.y)
-                            - camera_offset.0;
-                }
-            }
-        } else if let Some(map) = &map {
-            let clamped = move_offset.clamp(IVec2::new(-1, -1), IVec2::new(1, 1));
-            let center = IVec2::new(center_x, center_y) + clamped;
-
-            if map.is_walkable(center.x, center.y) {
-                let camera_delta = IVec2::new(clamped.x, -clamped.y);
-                camera_offset.0 -= camera_delta;
+        if let Some(map) = &map
+            && let Some(step) = slide_step(step, |step| {
+                let target = player.world_position + step;
+                map.is_walkable(target.x, target.y)
+            })
+        {
+            // the camera follows the player, in locked scenes only as far as keeps them in view
+            let world_position = player.world_position + step;
+            camera_offset.0 = camera_offset_for(
+                map,
+                world_position,
+                scene_lock.0,
+                Some(camera_offset.0),
+                size,
+            );
+            player.place(world_position, camera_offset.0, size);
 
-                player.position = center;
-                player.world_position =
-                    IVec2::new(player.position.x, size[1] as i32 - 1 - player.position.y)
-                        - camera_offset.0;
+            if let Some(footstep) = map
+                .get_tile(world_position.x, world_position.y)
+                .and_then(|tile| map.tile_def(tile.tile_type).footstep.as_deref())
+            {
+                audio.play(asset_server.load(footstep)).with_volume(0.15);
             }
         }
     }
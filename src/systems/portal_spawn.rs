@@ -1,4 +1,4 @@
-use crate::{objects::*, resources::*};
+use crate::{maps::*, objects::*, resources::*};
 use bevy::prelude::*;
 use bevy_ascii_terminal::Terminal;
 use rand::prelude::*;
@@ -19,8 +19,11 @@ pub fn spawn_portal_after_survival(
     mut scene_lock: ResMut<SceneLock>,
     camera_offset: Res<CameraOffset>,
     level: Res<Level>,
+    marker_query: Query<&MapMarker>,
+    floors: Res<Floors>,
 ) {
-    if level.as_ref() == &Level::Rest {
+    // deeper levels only open their portal on the last floor
+    if level.as_ref() == &Level::Rest || !floors.is_last(*level) {
         return;
     }
 
@@ -28,6 +31,17 @@ pub fn spawn_portal_after_survival(
         if portal_query.is_empty() {
             scene_lock.0 = true;
 
+            // prefer a portal placed by the level designer
+            let portal_markers: Vec<IVec2> = marker_query
+                .iter()
+                .filter(|marker| marker.kind == MarkerKind::Portal)
+                .map(|marker| marker.position)
+                .collect();
+            if let Some(portal_position) = portal_markers.choose(&mut rand::rng()) {
+                commands.spawn((Portal::new(*portal_position),));
+                return;
+            }
+
             if let Ok(terminal) = terminal_query.single() {
                 let terminal_size = terminal.size();
                 let width = terminal_size[0] as i32;
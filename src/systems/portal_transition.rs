@@ -41,7 +41,7 @@ pub fn portal_transition_system(
                         *level = match level.as_ref() {
                             Level::Survival => Level::Rest,
                             Level::Rest => Level::Survival,
-                            Level::Grassland | Level::Dungeon => Level::Rest,
+                            Level::Grassland | Level::Dungeon | Level::Cave => Level::Rest,
                         };
 
                         if transitioning_to_rest {
@@ -94,6 +94,7 @@ pub fn render_portal_transition(
     }
 
     if let (Ok(mut terminal), Ok(player)) = (query.single_mut(), player_query.single()) {
+        let size = terminal.size().as_ivec2();
         let screen_pos = player.world_position + camera_offset.0;
         let radius = (portal_transition.progress * 20.0) as i32;
         for dy in -radius..=radius {
@@ -102,7 +103,7 @@ pub fn render_portal_transition(
                 if distance <= radius as f32 && distance >= (radius - 1) as f32 {
                     let x = screen_pos.x + dx;
                     let y = screen_pos.y + dy;
-                    if x >= 0 && x < 80 && y >= 0 && y < 50 {
+                    if x >= 0 && x < size.x && y >= 0 && y < size.y {
                         let char = match portal_transition.progress {
                             p if p < 0.25 => '░',
                             p if p < 0.5 => '▒',
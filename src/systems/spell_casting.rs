@@ -1,6 +1,5 @@
 use crate::{objects::*, resources::*, spells::*};
 use bevy::prelude::*;
-use bevy_ascii_terminal::Terminal;
 #[derive(Resource)]
 pub struct SpellInputTimer(pub Timer);
 
@@ -67,20 +66,3 @@ pub fn spell_casting_system(
         }
     }
 }
-
-pub fn spell_render_system(mut query: Query<&mut Terminal>, player_query: Query<&Player>) {
-    if let Ok(mut terminal) = query.single_mut() {
-        if let Ok(player) = player_query.single() {
-            let mana_ratio = player.arcanum.mana / player.arcanum.max_mana;
-            let bar_height = 20;
-            let filled_height = (bar_height as f32 * mana_ratio) as usize;
-            for i in 0..bar_height {
-                if i < filled_height {
-                    terminal.put_char([0, 20 + i], '█');
-                } else {
-                    terminal.put_char([0, 20 + i], '░');
-                }
-            }
-        }
-    }
-}
@@ -0,0 +1,145 @@
+use crate::{
+    maps::*,
+    objects::*,
+    resources::*,
+    systems::{LevelScoped, place_player, spawn_map_objects},
+};
+use bevy::{ecs::entity_disabling::Disabled, prelude::*};
+use bevy_ascii_terminal::Terminal;
+
+/// everything that belongs to the floor the player is on
+type FloorEntities = Or<(
+    With<LevelScoped>,
+    With<Enemy>,
+    With<Boss>,
+    With<Orb>,
+    With<Portal>,
+)>;
+
+/// what a floor's map is picked or generated from
+type FloorSources<'w> = (
+    Res<'w, Level>,
+    Res<'w, MapSeed>,
+    Res<'w, MapHandles>,
+    Res<'w, Assets<Map>>,
+    Res<'w, TileRegistry>,
+    Option<Res<'w, PlaytestMap>>,
+);
+
+/// stepping onto stairs stashes the current floor and swaps in the one they lead to
+pub fn use_stairs(
+    mut commands: Commands,
+    player_query: Query<&Player>,
+    map: Option<Res<Map>>,
+    mut floors: ResMut<Floors>,
+    (level, map_seed, map_handles, maps, registry, playtest_map): FloorSources,
+    (floor_query, projectile_query): (
+        Query<Entity, FloorEntities>,
+        Query<Entity, With<Projectile>>,
+    ),
+    mut last_cell: Local<Option<(usize, IVec2)>>,
+) {
+    let (Ok(player), Some(map)) = (player_query.single(), map) else {
+        return;
+    };
+
+    // only a step onto the stairs counts, not arriving on them from another floor
+    let here = (floors.current, player.world_position);
+    let stepped = last_cell.is_some_and(|(floor, cell)| floor == here.0 && cell != here.1);
+    *last_cell = Some(here);
+    if !stepped {
+        return;
+    }
+
+    let position = player.world_position;
+    let Some(direction) = map
+        .get_tile(position.x, position.y)
+        .and_then(|tile| map.tile_def(tile.tile_type).stairs)
+    else {
+        return;
+    };
+
+    // play-tested maps are a single floor, and stairs off either end lead nowhere
+    let Some(floor) = floors
+        .current
+        .checked_add_signed(direction.floor_step())
+        .filter(|&floor| floor < level.floor_count() && playtest_map.is_none())
+    else {
+        return;
+    };
+
+    let (next_map, entities, first_visit) = match floors.take(floor) {
+        Some(stashed) => (stashed.map, stashed.entities, false),
+        None => {
+            let Some(next_map) =
+                build_floor_map(*level, floor, &map_handles, &maps, &registry, map_seed.0)
+            else {
+                return;
+            };
+            (next_map, Vec::new(), true)
+        }
+    };
+
+    let leaving: Vec<Entity> = floor_query.iter().collect();
+    for &entity in &leaving {
+        commands.entity(entity).insert(Disabled);
+    }
+    for entity in projectile_query.iter() {
+        commands.entity(entity).despawn();
+    }
+    let current = floors.current;
+    floors.stash(current, map.clone(), leaving);
+
+    for entity in entities {
+        commands.entity(entity).remove::<Disabled>();
+    }
+    commands.insert_resource(next_map);
+
+    floors.current = floor;
+    floors.arriving = Some(FloorArrival {
+        direction,
+        first_visit,
+    });
+}
+
+/// places the player on the floor they just went to, on the stairs leading back
+pub fn arrive_on_floor(
+    mut commands: Commands,
+    mut floors: ResMut<Floors>,
+    map: Option<Res<Map>>,
+    mut player_query: Query<&mut Player>,
+    mut camera_offset: ResMut<CameraOffset>,
+    scene_lock: Res<SceneLock>,
+    terminal_query: Query<&Terminal>,
+) {
+    let (Some(arrival), Some(map)) = (floors.arriving, map) else {
+        return;
+    };
+    floors.arriving = None;
+
+    if arrival.first_visit {
+        spawn_map_objects(&mut commands, &map);
+    }
+
+    if let (Ok(mut player), Ok(terminal)) = (player_query.single_mut(), terminal_query.single()) {
+        let position = map
+            .find_stairs(arrival.direction.opposite())
+            .or(map.marker(MarkerKind::PlayerStart))
+            .unwrap_or(IVec2::new(map.width as i32 / 2, map.height as i32 / 2));
+        place_player(
+            &mut player,
+            &map,
+            position,
+            &mut camera_offset.0,
+            scene_lock.0,
+            terminal.size(),
+        );
+    }
+}
+
+/// every level starts on its first floor, whatever was left on its other floors goes
+pub fn reset_floors(mut commands: Commands, mut floors: ResMut<Floors>) {
+    for entity in floors.reset() {
+        commands.entity(entity).try_despawn();
+    }
+}
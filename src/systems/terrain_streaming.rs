@@ -0,0 +1,33 @@
+use crate::{maps::*, objects::*};
+use bevy::prelude::*;
+use bevy_ascii_terminal::Terminal;
+
+/// keeps endless terrain generated as far around the player as the terminal can show, as
+/// they cross into new chunks or the terminal is resized
+pub fn stream_terrain(
+    player_query: Query<&Player>,
+    terminal_query: Query<&Terminal>,
+    map: Option<ResMut<Map>>,
+    mut last_streamed: Local<Option<(IVec2, UVec2)>>,
+) {
+    let (Ok(player), Ok(terminal), Some(mut map)) =
+        (player_query.single(), terminal_query.single(), map)
+    else {
+        return;
+    };
+
+    // only take the map mutably when there's streaming to do, so nothing else sees it change
+    let chunk = ChunkedTerrain::chunk_of(player.world_position);
+    let view_size = terminal.size();
+    let Some(terrain) = map.terrain.as_ref() else {
+        return;
+    };
+    if terrain.is_loaded(chunk) && *last_streamed == Some((chunk, view_size)) {
+        return;
+    }
+
+    *last_streamed = Some((chunk, view_size));
+    if let Some(terrain) = map.terrain.as_mut() {
+        terrain.stream(player.world_position, view_size);
+    }
+}
@@ -0,0 +1,209 @@
+use crate::{effects::*, maps::*, objects::*, resources::*, systems::*};
+use bevy::prelude::*;
+
+/// how close the player has to get to notice a hidden trap
+const SPOT_RADIUS: i32 = 2;
+
+/// springs traps on the player and on enemies alike, so hordes can be lured into them
+pub fn trigger_traps(
+    mut commands: Commands,
+    mut trap_query: Query<&mut Trap>,
+    mut player_query: Query<(Entity, &mut Player)>,
+    mut enemy_query: Query<(Entity, &mut Enemy), Without<Despawn>>,
+    mut kill_count: ResMut<KillCount>,
+    (mut damage_effect_timer, mut camera_effects): (
+        ResMut<DamageEffectTimer>,
+        ResMut<CameraEffects>,
+    ),
+    (time, map): (Res<Time>, Option<Res<Map>>),
+) {
+    let Ok((player_entity, mut player)) = player_query.single_mut() else {
+        return;
+    };
+
+    for mut trap in trap_query.iter_mut() {
+        trap.cooldown.tick(time.delta());
+
+        let position = trap.position;
+        let player_on_trap = player.world_position == position;
+        let enemy_on_trap = enemy_query
+            .iter()
+            .any(|(_, enemy)| enemy.position == position);
+        let occupied = player_on_trap || enemy_on_trap;
+
+        match trap.kind {
+            TrapKind::Spikes => {
+                if occupied && trap.cooldown.finished() {
+                    trap.cooldown.reset();
+                    trap.hidden = false;
+
+                    if player_on_trap {
+                        hurt_player(
+                            &mut commands,
+                            player_entity,
+                            &mut player,
+                            trap.damage,
+                            map.as_deref(),
+                            &mut damage_effect_timer,
+                            &mut camera_effects,
+                        );
+                    }
+                    for (enemy_entity, mut enemy) in enemy_query.iter_mut() {
+                        if enemy.position == position {
+                            hurt_enemy(
+                                &mut commands,
+                                enemy_entity,
+                                &mut enemy,
+                                trap.damage,
+                                &mut kill_count,
+                            );
+                        }
+                    }
+                }
+            }
+            TrapKind::PressurePlate { launcher } => {
+                // only fires as something steps on, standing still doesn't keep it firing
+                if occupied && !trap.pressed {
+                    let direction = (position - launcher).signum();
+                    commands.spawn((Dart::new(launcher, direction, trap.damage), LevelScoped));
+                    trap.hidden = false;
+                }
+            }
+        }
+
+        trap.pressed = occupied;
+    }
+}
+
+/// hidden traps right next to the player, and in plain sight, are spotted
+pub fn spot_hidden_traps(
+    mut trap_query: Query<&mut Trap>,
+    player_query: Query<&Player>,
+    map: Option<Res<Map>>,
+) {
+    let Ok(player) = player_query.single() else {
+        return;
+    };
+
+    for mut trap in trap_query.iter_mut().filter(|trap| trap.hidden) {
+        let close = (trap.position - player.world_position).abs().max_element() <= SPOT_RADIUS;
+        let in_view = map.as_ref().is_none_or(|map| map.is_visible(trap.position));
+        if close && in_view {
+            trap.hidden = false;
+        }
+    }
+}
+
+pub fn move_darts(
+    mut commands: Commands,
+    mut dart_query: Query<(Entity, &mut Dart), Without<Despawn>>,
+    mut player_query: Query<(Entity, &mut Player)>,
+    mut enemy_query: Query<(Entity, &mut Enemy), Without<Despawn>>,
+    mut kill_count: ResMut<KillCount>,
+    (mut damage_effect_timer, mut camera_effects): (
+        ResMut<DamageEffectTimer>,
+        ResMut<CameraEffects>,
+    ),
+    (time, map): (Res<Time>, Option<Res<Map>>),
+) {
+    let Ok((player_entity, mut player)) = player_query.single_mut() else {
+        return;
+    };
+
+    for (dart_entity, mut dart) in dart_query.iter_mut() {
+        dart.lifetime -= time.delta_secs();
+        if dart.lifetime <= 0.0 {
+            commands.entity(dart_entity).insert(Despawn);
+            continue;
+        }
+
+        let previous_position = dart.position;
+        let movement = dart.direction.as_vec2() * dart.speed * time.delta_secs();
+        dart.precise_position += movement;
+        dart.position = dart.precise_position.round().as_ivec2();
+
+        // check every cell crossed this frame so a fast dart can't skip over anything.
+        // the cell it left is skipped, darts start out inside their launcher's wall
+        for cell in line(previous_position, dart.position).skip(1) {
+            if map
+                .as_ref()
+                .is_some_and(|map| map.blocks_projectiles(cell.x, cell.y))
+            {
+                commands.entity(dart_entity).insert(Despawn);
+                break;
+            }
+
+            if player.world_position == cell {
+                hurt_player(
+                    &mut commands,
+                    player_entity,
+                    &mut player,
+                    dart.damage,
+                    map.as_deref(),
+                    &mut damage_effect_timer,
+                    &mut camera_effects,
+                );
+                commands.entity(dart_entity).insert(Despawn);
+                break;
+            }
+
+            if let Some((enemy_entity, mut enemy)) = enemy_query
+                .iter_mut()
+                .find(|(_, enemy)| enemy.position == cell)
+            {
+                hurt_enemy(
+                    &mut commands,
+                    enemy_entity,
+                    &mut enemy,
+                    dart.damage,
+                    &mut kill_count,
+                );
+                commands.entity(dart_entity).insert(Despawn);
+                break;
+            }
+        }
+    }
+}
+
+/// every hit the player takes goes through here, so they all hurt and shake the same
+pub fn hurt_player(
+    commands: &mut Commands,
+    player_entity: Entity,
+    player: &mut Player,
+    damage: f32,
+    map: Option<&Map>,
+    damage_effect_timer: &mut DamageEffectTimer,
+    camera_effects: &mut CameraEffects,
+) {
+    // safe zones shield the player from any damage
+    if map.is_some_and(|map| map.is_safe(player.world_position)) {
+        return;
+    }
+
+    player.health -= damage;
+    commands.entity(player_entity).insert(StatusEffect {
+        name: "Hurt",
+        color: Color::linear_rgb(1.0, 0.0, 0.0),
+    });
+    damage_effect_timer.0.reset();
+    camera_effects.player_hit(damage);
+}
+
+fn hurt_enemy(
+    commands: &mut Commands,
+    enemy_entity: Entity,
+    enemy: &mut Enemy,
+    damage: f32,
+    kill_count: &mut KillCount,
+) {
+    if enemy.health <= 0.0 {
+        return;
+    }
+
+    enemy.health -= damage;
+    if enemy.health <= 0.0 {
+        commands.spawn(Orb::new(enemy.position, 10));
+        commands.entity(enemy_entity).insert(Despawn);
+        kill_count.enemies += 1;
+    }
+}
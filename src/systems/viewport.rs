@@ -0,0 +1,46 @@
+use crate::{maps::Map, objects::*, resources::*, systems::camera_offset_for};
+use bevy::{prelude::*, window::*};
+use bevy_ascii_terminal::{Terminal, render::UpdateTerminalViewportEvent};
+
+/// refits the terminal grid whenever the window changes size, and places the camera afresh
+/// for the new size so it can't drift from where a level would have put it
+pub fn resize_terminal(
+    mut resize_events: EventReader<WindowResized>,
+    viewport: Res<ViewportConfig>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    mut terminal_query: Query<&mut Terminal>,
+    mut viewport_events: EventWriter<UpdateTerminalViewportEvent>,
+    mut player_query: Query<&mut Player>,
+    (mut camera_offset, map, scene_lock): (ResMut<CameraOffset>, Option<Res<Map>>, Res<SceneLock>),
+) {
+    if resize_events.is_empty() {
+        return;
+    }
+    resize_events.clear();
+
+    let (Ok(window), Ok(mut terminal)) = (window_query.single(), terminal_query.single_mut())
+    else {
+        return;
+    };
+    let old_size = terminal.size();
+    let size = viewport.terminal_size(window.physical_size());
+    if size == old_size {
+        return;
+    }
+
+    terminal.resize(size);
+    // the terminal camera only refits its viewport when asked to
+    viewport_events.write(UpdateTerminalViewportEvent);
+
+    if let (Ok(mut player), Some(map)) = (player_query.single_mut(), map) {
+        let world_position = player.world_position;
+        camera_offset.0 = camera_offset_for(
+            &map,
+            world_position,
+            scene_lock.0,
+            Some(camera_offset.0),
+            size,
+        );
+        player.place(world_position, camera_offset.0, size);
+    }
+}